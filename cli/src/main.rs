@@ -0,0 +1,455 @@
+//! Converts text to morse code and back using the same tables as the firmware
+//!
+//! Usage: `morse <encode|decode|wav|listen> [OPTIONS] [FILE...]`
+//!
+//! Files are read in order, or stdin when none are given or a file is `-`.
+//! Each line is converted on its own. Morse code is written as it is shown in serial mode,
+//! with a space between letters and two between words, or rendered as a tone in a WAV file.
+//! WAV files of a tone can be listened to at the frequency given, learning the speed as they go
+//! from the speed given, writing each word heard with the time it started.
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::{
+	env,
+	fs::{self, File},
+	io::{self, BufReader, BufWriter, Read},
+	process::ExitCode,
+	str::FromStr,
+};
+
+use morse::{
+	alphabet::Table,
+	code::{Code, Mark, Marks},
+	decode,
+	decoder::Decoded,
+	encode,
+	error::Error,
+	read_standalone_prosigns,
+	wav::{self, Tone},
+};
+
+const USAGE: &str = "Usage: morse <encode|decode|wav|listen> [OPTIONS] [FILE...]
+
+Options:
+  -h, --help            Show this help
+  -a, --alphabet NAME   The alphabet to convert with
+  -o, --output FILE     The WAV file to write, needed for wav and only used by it
+  --frequency HZ        Frequency of the tone (600)
+  --wpm WPM             Speed of the letters in words per minute (6)
+  --farnsworth WPM      Slower overall speed, stretching the gaps between letters and words
+  --weight PERCENT      Percentage of a dot and the gap after it that the dot is on for (50)
+  --sample-rate HZ      Samples per second (44100)
+  --envelope MS         Rise and fall of each mark in milliseconds (5)";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Command {
+	Encode,
+	Decode,
+	/// Renders the text as a tone in a WAV file
+	Wav,
+	/// Decodes a tone in WAV files
+	Listen,
+}
+
+/// Everything given after the command
+struct Options {
+	alphabet: Table,
+	paths:    Vec<String>,
+	output:   Option<String>,
+	tone:     Tone,
+}
+
+fn main() -> ExitCode {
+	let mut args = env::args().skip(1);
+
+	if env::args()
+		.skip(1)
+		.any(|arg| arg == "-h" || arg == "--help")
+	{
+		println!("{}", usage_text());
+		return ExitCode::SUCCESS;
+	}
+
+	let command = match args.next().as_deref() {
+		Some("encode") => Command::Encode,
+		Some("decode") => Command::Decode,
+		Some("wav") => Command::Wav,
+		Some("listen") => Command::Listen,
+		_ => return usage(),
+	};
+
+	let Some(Options {
+		alphabet,
+		mut paths,
+		output,
+		tone,
+	}) = parse_options(args)
+	else {
+		return usage();
+	};
+
+	if let Some(reason) = unsupported(command, alphabet) {
+		eprintln!("{reason}");
+		return ExitCode::FAILURE;
+	}
+
+	if command == Command::Wav && output.is_none() {
+		return usage();
+	}
+	if command != Command::Wav && output.is_some() {
+		eprintln!("--output is only used by wav");
+		return ExitCode::FAILURE;
+	}
+
+	if paths.is_empty() {
+		paths.push("-".into());
+	}
+
+	let mut failed = false;
+	let mut codes = Vec::new();
+
+	for path in &paths {
+		// WAV files are read whole rather than as lines of text
+		if command == Command::Listen {
+			failed |= !listen_file(path, &tone, alphabet);
+			continue;
+		}
+
+		let text = match read_input(path) {
+			Ok(text) => text,
+			Err(error) => {
+				eprintln!("{path}: {error}");
+				failed = true;
+				continue;
+			}
+		};
+
+		for (index, line) in text.lines().enumerate() {
+			let converted = match command {
+				Command::Encode => encode_line(line, alphabet).map(|marks| println!("{marks}")),
+				Command::Decode => decode_line(line, alphabet).map(|text| println!("{text}")),
+				Command::Wav => encode(line, &alphabet).map(|line_codes| {
+					// Lines are joined with a space between words
+					if !codes.is_empty() {
+						codes.push(Code::Space);
+					}
+					codes.extend(line_codes);
+				}),
+				Command::Listen => unreachable!(),
+			};
+
+			if let Err(error) = converted {
+				eprintln!("{path}:{}: Error: {error}", index + 1);
+				failed = true;
+			}
+		}
+	}
+
+	if let Some(output) = output {
+		let samples = wav::render(&codes, &tone);
+		let written = File::create(&output)
+			.and_then(|file| wav::write(BufWriter::new(file), &samples, tone.sample_rate));
+
+		if let Err(error) = written {
+			eprintln!("{output}: {error}");
+			failed = true;
+		}
+	}
+
+	if failed {
+		ExitCode::FAILURE
+	} else {
+		ExitCode::SUCCESS
+	}
+}
+
+/// Parses the options and files after the command
+///
+/// # Returns
+/// * `Option<Options>` - The options, or `None` if any are unknown or missing their value
+fn parse_options<I: Iterator<Item = String>>(mut args: I) -> Option<Options> {
+	let mut options = Options {
+		alphabet: Table::default(),
+		paths:    Vec::new(),
+		output:   None,
+		tone:     Tone::default(),
+	};
+	let mut farnsworth_wpm = None;
+	let tone = &mut options.tone;
+
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-a" | "--alphabet" => options.alphabet = Table::from_name(&args.next()?)?,
+			"-o" | "--output" => options.output = Some(args.next()?),
+			"--frequency" => tone.frequency = parse_positive(args.next())?,
+			"--wpm" => tone.timing.wpm = parse_positive(args.next())?,
+			"--farnsworth" => farnsworth_wpm = Some(parse_positive(args.next())?),
+			"--weight" => {
+				tone.timing.weight =
+					parse_positive(args.next()).filter(|weight| *weight < 100.0)?;
+			}
+			"--sample-rate" => {
+				// Bytes per second have to fit in the WAV header as well
+				tone.sample_rate = args
+					.next()?
+					.parse()
+					.ok()
+					.filter(|rate: &u32| *rate > 0 && rate.checked_mul(2).is_some())?;
+			}
+			"--envelope" => tone.envelope = parse_positive(args.next())?,
+			// `-` alone is stdin rather than an option
+			_ if arg.starts_with('-') && arg != "-" => return None,
+			_ => options.paths.push(arg),
+		}
+	}
+
+	tone.timing.farnsworth_wpm = farnsworth_wpm.unwrap_or(tone.timing.wpm);
+
+	Some(options)
+}
+
+/// Parses a number greater than zero
+fn parse_positive<T: FromStr + Copy + Into<f64>>(value: Option<String>) -> Option<T> {
+	value
+		.and_then(|value| value.parse().ok())
+		.filter(|value: &T| {
+			let value: f64 = (*value).into();
+			value.is_finite() && value > 0.0
+		})
+}
+
+/// Writes the usage to stderr, for arguments that can't be used
+fn usage() -> ExitCode {
+	eprintln!("{}", usage_text());
+	ExitCode::FAILURE
+}
+
+/// The usage along with the alphabet names
+fn usage_text() -> String {
+	let names: Vec<&str> = Table::ALL.iter().map(|table| table.name()).collect();
+	format!("{USAGE}\n\nAlphabets: {}", names.join(", "))
+}
+
+/// Explains why a command can't be used with an alphabet
+const fn unsupported(command: Command, alphabet: Table) -> Option<&'static str> {
+	match (command, alphabet) {
+		(Command::Decode, Table::American) => Some(
+			"American Morse can't be decoded from text, as a space inside a letter looks the same as a gap between letters",
+		),
+		(Command::Listen, Table::American) => Some(
+			"American Morse can't be listened to, as its letters have spaces and long dashes inside them",
+		),
+		_ => None,
+	}
+}
+
+/// Reads a file, or stdin when the path is `-`
+fn read_input(path: &str) -> io::Result<String> {
+	if path == "-" {
+		let mut text = String::new();
+		io::stdin().read_to_string(&mut text)?;
+		Ok(text)
+	} else {
+		fs::read_to_string(path)
+	}
+}
+
+/// Listens to a WAV file, writing each word heard with the time it started
+///
+/// # Returns
+/// * `bool` - Whether the file could be read and every letter was known
+fn listen_file(path: &str, tone: &Tone, alphabet: Table) -> bool {
+	let recording = if path == "-" {
+		wav::read(io::stdin().lock())
+	} else {
+		File::open(path).and_then(|file| wav::read(BufReader::new(file)))
+	};
+
+	let recording = match recording {
+		Ok(recording) => recording,
+		Err(error) => {
+			eprintln!("{path}: {error}");
+			return false;
+		}
+	};
+
+	let mut known = true;
+	let mut word: Option<(u64, String)> = None;
+
+	for (position, received) in wav::listen(&recording, tone, &alphabet)
+		.into_iter()
+		.enumerate()
+	{
+		let (start, text) = word.get_or_insert_with(|| (received.time, String::new()));
+
+		match received.decoded {
+			Decoded::Char(' ') => {
+				println!("{}  {text}", seconds(*start));
+				word = None;
+			}
+			Decoded::Char(char) => text.push(char),
+			Decoded::Digraph(first, second) => {
+				text.push(first);
+				text.push(second);
+			}
+			Decoded::Prosign(prosign) => {
+				text.push('<');
+				text.push_str(prosign.name());
+				text.push('>');
+			}
+			// Letters that aren't in the alphabet are reported and left out
+			Decoded::Unknown => {
+				let error = Error::UnknownCode {
+					code: received.code,
+					position,
+				};
+				eprintln!("{path}: {}: Error: {error}", seconds(received.time));
+				known = false;
+			}
+			Decoded::None => {}
+		}
+	}
+
+	if let Some((start, text)) = word {
+		println!("{}  {text}", seconds(start));
+	}
+
+	known
+}
+
+/// Formats a time in microseconds as seconds
+fn seconds(time: u64) -> String {
+	#[allow(clippy::cast_precision_loss)]
+	let seconds = time as f64 / 1_000_000.0;
+	format!("{seconds:>10.3} s")
+}
+
+/// Converts a line of text to dots and dashes, as serial mode writes them
+///
+/// # Errors
+/// * [`Error::UnknownCharacter`] - If a character isn't in the alphabet
+/// * [`Error::UnknownProsign`] - If a prosign name isn't known
+fn encode_line(line: &str, alphabet: Table) -> Result<String, Error> {
+	let mut marks = String::new();
+
+	for code in encode(line, &alphabet)? {
+		marks.push_str(&code.to_marks());
+		marks.push(' ');
+	}
+
+	Ok(marks.trim_end().into())
+}
+
+/// Converts a line of dots and dashes back to text
+///
+/// Letters are separated by a space, and every extra space or `/` is a space between words.
+/// A blank line decodes to an empty one. Punctuation sent as a word on its own is read as the
+/// prosign sharing its marks, as button mode reads it.
+///
+/// # Errors
+/// * [`Error::UnknownCharacter`] - If a letter has anything other than `.` and `-`
+/// * [`Error::TooManyMarks`] - If a letter has more marks than a code can hold
+/// * [`Error::UnknownCode`] - If a letter isn't in the alphabet
+fn decode_line(line: &str, alphabet: Table) -> Result<String, Error> {
+	let trimmed = line.trim();
+	if trimmed.is_empty() {
+		return Ok(String::new());
+	}
+
+	let mut codes = Vec::new();
+	// Positions count from the start of the line, before the whitespace trimmed off
+	let mut position = line.chars().take_while(|char| char.is_whitespace()).count();
+
+	for letter in trimmed.split(' ') {
+		let code = if letter.is_empty() || letter == "/" {
+			Code::Space
+		} else {
+			let mut marks = Marks::new();
+
+			for (index, character) in letter.chars().enumerate() {
+				let mark = match character {
+					'.' => Mark::Dot,
+					'-' => Mark::Dash,
+					_ => {
+						return Err(Error::UnknownCharacter {
+							character,
+							position: position + index,
+						})
+					}
+				};
+				marks.push(mark).map_err(|_| Error::TooManyMarks)?;
+			}
+
+			Code::from_marks(marks, &alphabet)
+		};
+
+		codes.push(code);
+		position += letter.chars().count() + 1;
+	}
+
+	read_standalone_prosigns(&mut codes, &alphabet);
+
+	decode(&codes, &alphabet)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decodes_a_line_of_marks() {
+		let decoded = decode_line(".--. .- .-. .. ...  -.-. --.- / -.. .", Table::default());
+		assert_eq!(decoded.unwrap(), "paris cq de");
+	}
+
+	#[test]
+	fn decodes_prosigns_sent_on_their_own_as_they_were_encoded() {
+		let alphabet = Table::default();
+		let marks = encode_line("<AR> <BT> <KN> <AS>", alphabet).unwrap();
+
+		assert_eq!(
+			decode_line(&marks, alphabet).unwrap(),
+			"<AR> <BT> <KN> <AS>"
+		);
+	}
+
+	#[test]
+	fn rejects_unknown_options() {
+		let args = ["--wmp", "20"].map(String::from);
+		assert!(parse_options(args.into_iter()).is_none());
+
+		let args = ["-", "--wpm", "20"].map(String::from);
+		assert_eq!(parse_options(args.into_iter()).unwrap().paths, ["-"]);
+	}
+
+	#[test]
+	fn rejects_sample_rates_too_high_for_a_wav_file() {
+		let args = ["--sample-rate", "3000000000"].map(String::from);
+		assert!(parse_options(args.into_iter()).is_none());
+
+		let args = ["--sample-rate", "48000"].map(String::from);
+		assert_eq!(
+			parse_options(args.into_iter()).unwrap().tone.sample_rate,
+			48000
+		);
+	}
+
+	#[test]
+	fn decodes_a_blank_line_to_an_empty_one() {
+		assert_eq!(decode_line("", Table::default()).unwrap(), "");
+		assert_eq!(decode_line(" \t ", Table::default()).unwrap(), "");
+	}
+
+	#[test]
+	fn reports_positions_from_the_start_of_the_line() {
+		let error = decode_line("  .- -x", Table::default()).unwrap_err();
+		assert_eq!(
+			error,
+			Error::UnknownCharacter {
+				character: 'x',
+				position:  6,
+			}
+		);
+	}
+}
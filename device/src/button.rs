@@ -0,0 +1,408 @@
+use heapless::Vec;
+use morse::{
+	alphabet::{american, Alphabet, Table},
+	code::{Code, Element, Elements, Mark::Dash},
+	error::Error,
+	keying::Timing,
+	read_standalone_prosigns,
+	timing::{Finished, Span, SpanDecoder},
+	BUFFER_LENGTH,
+};
+
+use crate::{
+	hal::{Clock, Indicators, KeyEvent, KeyInput, Led, TextSink},
+	serial::write_error,
+	trace::Recorder,
+};
+
+/// How long the button is held in milliseconds to finish the message
+pub const PASSAGE_END_LENGTH: u32 = 1500;
+
+/// How often the button's events are taken in milliseconds, they are timestamped as they happen
+const POLL_INTERVAL: u32 = 1;
+
+/// Speed the lengths of presses and gaps are learnt from when keying by hand,
+/// with a dash from 500 ms and a gap between words from about 2 s
+pub const START_TIMING: Timing = Timing {
+	wpm:            4.8,
+	farnsworth_wpm: 4.0,
+	weight:         50.0,
+};
+
+/// Length of a dot when keying American Morse, the other lengths are multiples of it
+const AMERICAN_UNIT: u32 = 200;
+const AMERICAN_LETTER_TIME_LENGTH: u32 = AMERICAN_UNIT * 5 / 2;
+const AMERICAN_WORD_TIME_LENGTH: u32 = AMERICAN_UNIT * 5;
+
+/// Scans the button for input and returns a morse code buffer
+///
+/// The boundaries between dots and dashes, and between the gaps inside letters, between letters
+/// and between words, follow the operator's speed as it is learnt over the message.
+/// A letter is decoded from its presses classified again with what has been learnt by its end,
+/// and its marks are echoed then, so the echo matches the letter even where the LEDs showed a
+/// press differently while it was held. Punctuation keyed as a word on its own is read as the
+/// prosign sharing its marks.
+///
+/// # Arguments
+/// * `pin_set` - The pins to use for input and output
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+/// * `alphabet` - The alphabet letters are recognised from
+/// * `timing` - The speed the lengths are learnt from, such as [`START_TIMING`]
+/// * `first_press` - The press that starts the message
+/// * `recorder` - Records every press and release of the button
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code buffer
+pub fn scan<P, C, S>(
+	pin_set: &mut P,
+	clock: &mut C,
+	serial: &mut S,
+	alphabet: Table,
+	timing: &Timing,
+	first_press: KeyEvent,
+	recorder: &mut Recorder,
+) -> [Code; BUFFER_LENGTH]
+where
+	P: KeyInput + Indicators,
+	C: Clock,
+	S: TextSink,
+{
+	let mut codes: Vec<Code, BUFFER_LENGTH> = Vec::new();
+	let mut decoder = SpanDecoder::new(&alphabet, timing);
+
+	let mut key = KeyTimer::new(first_press);
+	recorder.record(first_press.time, first_press.pressed);
+
+	'scan: loop {
+		let now = clock.now();
+
+		while let Some(event) = pin_set.next_event(now) {
+			recorder.record(event.time, event.pressed);
+
+			let Some(span) = key.update(event) else {
+				continue;
+			};
+
+			if !span.on {
+				// A gap that turns out longer than it seemed can still finish a letter or word
+				if let Err(error) = handle_finished(&mut codes, decoder.push(span), pin_set, serial)
+				{
+					write_error(serial, &error);
+					break 'scan;
+				}
+			} else if is_passage_end(span) {
+				if let Some(letter) = decoder.finish() {
+					if let Err(error) = handle_letter(&mut codes, letter.code, pin_set, serial) {
+						write_error(serial, &error);
+					}
+				}
+				break 'scan;
+			} else {
+				handle_mark(&mut decoder, span, serial);
+			}
+		}
+
+		let held = key.held(now);
+
+		if key.pressed {
+			button_on_event(held, pin_set, decoder.mark(held) == Dash);
+		} else {
+			if let Err(error) = handle_finished(&mut codes, decoder.wait(held), pin_set, serial) {
+				write_error(serial, &error);
+				break;
+			}
+
+			pin_set.set(Led::ShortPress, false);
+			pin_set.set(Led::LongPress, false);
+		}
+
+		clock.delay_ms(POLL_INTERVAL);
+	}
+
+	pin_set.leds_off();
+
+	let mut codes = finalise_codes(codes);
+	read_standalone_prosigns(&mut codes, &alphabet);
+
+	codes
+}
+
+/// Scans the button for American Morse input and returns a morse code buffer
+///
+/// Presses are classified into elements by their length, and a gap a little longer
+/// than between elements adds a space inside the letter.
+///
+/// # Arguments
+/// * `pin_set` - The pins to use for input and output
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+/// * `first_press` - The press that starts the message
+/// * `recorder` - Records every press and release of the button
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code buffer
+pub fn scan_american<P, C, S>(
+	pin_set: &mut P,
+	clock: &mut C,
+	serial: &mut S,
+	first_press: KeyEvent,
+	recorder: &mut Recorder,
+) -> [Code; BUFFER_LENGTH]
+where
+	P: KeyInput + Indicators,
+	C: Clock,
+	S: TextSink,
+{
+	let mut codes: Vec<Code, BUFFER_LENGTH> = Vec::new();
+	let mut current_code = Elements::new();
+
+	let mut key = KeyTimer::new(first_press);
+	recorder.record(first_press.time, first_press.pressed);
+	// Whether the gap since the last press has already finished a word
+	let mut word_ended = false;
+
+	'scan: loop {
+		let now = clock.now();
+
+		while let Some(event) = pin_set.next_event(now) {
+			recorder.record(event.time, event.pressed);
+
+			let Some(span) = key.update(event) else {
+				continue;
+			};
+
+			if !span.on {
+				if !current_code.is_empty()
+					&& american::is_space(span.duration / 1000, AMERICAN_UNIT)
+				{
+					handle_element(&mut current_code, Element::Space, serial);
+				}
+				word_ended = false;
+			} else if is_passage_end(span) {
+				if !current_code.is_empty() {
+					if let Err(error) =
+						handle_american_letter(&mut codes, &mut current_code, pin_set, serial)
+					{
+						write_error(serial, &error);
+					}
+				}
+				break 'scan;
+			} else {
+				let element = american::classify_press(span.duration / 1000, AMERICAN_UNIT);
+				handle_element(&mut current_code, element, serial);
+			}
+		}
+
+		let held = key.held(now);
+
+		if key.pressed {
+			button_on_event(held, pin_set, held / 1000 > AMERICAN_UNIT * 3 / 2);
+		} else {
+			if held >= AMERICAN_LETTER_TIME_LENGTH * 1000 && !current_code.is_empty() {
+				if let Err(error) =
+					handle_american_letter(&mut codes, &mut current_code, pin_set, serial)
+				{
+					write_error(serial, &error);
+					break;
+				}
+			}
+
+			if held >= AMERICAN_WORD_TIME_LENGTH * 1000 && !word_ended {
+				word_ended = true;
+
+				if let Err(error) = handle_word(pin_set, serial, &mut codes) {
+					write_error(serial, &error);
+					break;
+				}
+			}
+
+			pin_set.set(Led::ShortPress, false);
+			pin_set.set(Led::LongPress, false);
+		}
+
+		clock.delay_ms(POLL_INTERVAL);
+	}
+
+	pin_set.leds_off();
+
+	finalise_codes(codes)
+}
+
+/// Times the button from its events, keeping when it was last pressed or released
+struct KeyTimer {
+	pressed: bool,
+	/// Microseconds since the clock started
+	changed: u64,
+}
+
+impl KeyTimer {
+	/// # Arguments
+	/// * `first_press` - The press scanning starts from
+	const fn new(first_press: KeyEvent) -> Self {
+		Self {
+			pressed: first_press.pressed,
+			changed: first_press.time,
+		}
+	}
+
+	/// Updates the state of the button
+	///
+	/// # Returns
+	/// * `Option<Span>` - The span the event finishes, `None` if the button was already that way
+	fn update(&mut self, event: KeyEvent) -> Option<Span> {
+		if event.pressed == self.pressed {
+			return None;
+		}
+
+		let span = Span {
+			on:       self.pressed,
+			duration: self.held(event.time),
+		};
+		self.pressed = event.pressed;
+		self.changed = event.time;
+
+		Some(span)
+	}
+
+	/// Microseconds the button has been pressed or released for by a time
+	fn held(&self, now: u64) -> u32 {
+		u32::try_from(now.saturating_sub(self.changed)).unwrap_or(u32::MAX)
+	}
+}
+
+/// Whether a press was held long enough to finish the message
+const fn is_passage_end(press: Span) -> bool { press.duration > PASSAGE_END_LENGTH * 1000 }
+
+/// Shows how a press held so far would be classified
+///
+/// # Arguments
+/// * `held` - Microseconds the button has been held for
+/// * `long_press` - Whether the button has been held long enough for a dash
+fn button_on_event<I: Indicators>(held: u32, pin_set: &mut I, long_press: bool) {
+	if held > PASSAGE_END_LENGTH * 1000 {
+		pin_set.set(Led::PassageEnd, true);
+
+		pin_set.set(Led::ShortPress, false);
+		pin_set.set(Led::LongPress, false);
+	} else {
+		pin_set.set(Led::ShortPress, true);
+		if long_press {
+			pin_set.set(Led::LongPress, true);
+		}
+	}
+
+	pin_set.set(Led::Word, false);
+	pin_set.set(Led::Letter, false);
+}
+
+/// Handles button release event for adding mark to current letter
+///
+/// The mark is only classified once the letter is finished, with what has been learnt by then.
+/// A letter that is already full is reported and dropped, starting a new letter from the mark.
+fn handle_mark<A, S>(decoder: &mut SpanDecoder<A>, press: Span, serial: &mut S)
+where
+	A: Alphabet + ?Sized,
+	S: TextSink,
+{
+	if decoder.is_letter_full() {
+		write_error(serial, &Error::TooManyMarks);
+		decoder.clear_letter();
+	}
+	decoder.push(press);
+}
+
+/// Handles the letters and spaces a gap has finished
+fn handle_finished<I: Indicators, S: TextSink>(
+	codes: &mut Vec<Code, BUFFER_LENGTH>,
+	finished: Finished,
+	pin_set: &mut I,
+	serial: &mut S,
+) -> Result<(), Error> {
+	for received in finished {
+		if received.code == Code::Space {
+			handle_word(pin_set, serial, codes)?;
+		} else {
+			handle_letter(codes, received.code, pin_set, serial)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Handles button release event for finishing letter, echoing the marks it was decoded from
+fn handle_letter<I: Indicators, S: TextSink>(
+	codes: &mut Vec<Code, BUFFER_LENGTH>,
+	code: Code,
+	pin_set: &mut I,
+	serial: &mut S,
+) -> Result<(), Error> {
+	pin_set.set(Led::Letter, true);
+
+	codes.push(code).map_err(|_| Error::BufferOverflow)?;
+
+	serial.write(code.to_marks().as_bytes());
+	serial.write(b" ");
+
+	Ok(())
+}
+
+/// Handles button release event for adding an element to the current American Morse letter
+///
+/// A letter that is already full is reported and dropped, starting a new letter from the element.
+fn handle_element<S: TextSink>(current_code: &mut Elements, element: Element, serial: &mut S) {
+	if current_code.is_full() {
+		write_error(serial, &Error::TooManyMarks);
+		*current_code = Elements::new();
+	}
+	serial.write(element.symbol().encode_utf8(&mut [0; 4]).as_bytes());
+	current_code.push(element).unwrap();
+}
+
+/// Handles button release event for finishing an American Morse letter
+fn handle_american_letter<I: Indicators, S: TextSink>(
+	codes: &mut Vec<Code, BUFFER_LENGTH>,
+	current_code: &mut Elements,
+	pin_set: &mut I,
+	serial: &mut S,
+) -> Result<(), Error> {
+	pin_set.set(Led::Letter, true);
+
+	codes
+		.push(Code::American(*current_code))
+		.map_err(|_| Error::BufferOverflow)?;
+	*current_code = Elements::new();
+
+	serial.write(b" ");
+
+	Ok(())
+}
+
+/// Handles button release event for finishing word
+fn handle_word<I: Indicators, S: TextSink>(
+	pin_set: &mut I,
+	serial: &mut S,
+	codes: &mut Vec<Code, BUFFER_LENGTH>,
+) -> Result<(), Error> {
+	pin_set.set(Led::Word, true);
+	serial.write(b"  ");
+	codes.push(Code::Space).map_err(|_| Error::BufferOverflow)
+}
+
+/// Formats finished code set properly
+fn finalise_codes(mut codes: Vec<Code, BUFFER_LENGTH>) -> [Code; BUFFER_LENGTH] {
+	// Adds space to end of code for blinking, unless the buffer is already full
+	if let Some(last) = codes.last() {
+		match last {
+			Code::Space => {}
+			_ => codes.push(Code::Space).unwrap_or_default(),
+		}
+	}
+
+	// Fills rest of vec to be able to convert to array
+	while !codes.is_full() {
+		codes.push(Code::None).unwrap();
+	}
+
+	codes.into_array().unwrap()
+}
@@ -0,0 +1,70 @@
+//! Filters out the bounces of a mechanical key
+//!
+//! The contacts of a straight key bounce for a few milliseconds as they close and open,
+//! which reads as a burst of very short presses. A change is only passed on once the key
+//! has stayed that way for the debounce window, timed from the last edge of the burst.
+
+use crate::hal::{Indicators, KeyEvent, KeyInput, Led};
+
+/// A key with its bounces filtered out, passing on the LEDs of the key it wraps
+pub struct Debounced<K: KeyInput> {
+	key:       K,
+	/// Microseconds the key has to stay pressed or released for
+	window:    u32,
+	pressed:   bool,
+	/// The last edge to the other state, waiting out the window
+	candidate: Option<KeyEvent>,
+	/// An edge taken from the key after the window was over, to be looked at next
+	unread:    Option<KeyEvent>,
+}
+
+impl<K: KeyInput> Debounced<K> {
+	/// # Arguments
+	/// * `key` - The key to take edges from
+	/// * `window` - Microseconds the key has to stay pressed or released for, `0` passes every edge on
+	#[must_use]
+	pub const fn new(key: K, window: u32) -> Self {
+		Self {
+			key,
+			window,
+			pressed: false,
+			candidate: None,
+			unread: None,
+		}
+	}
+
+	/// Whether an edge has waited out the window by a time
+	fn is_settled(&self, candidate: KeyEvent, time: u64) -> bool {
+		time >= candidate.time + u64::from(self.window)
+	}
+}
+
+impl<K: KeyInput> KeyInput for Debounced<K> {
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent> {
+		while let Some(edge) = self.unread.take().or_else(|| self.key.next_event(now)) {
+			if let Some(candidate) = self.candidate {
+				if self.is_settled(candidate, edge.time) {
+					self.unread = Some(edge);
+					self.candidate = None;
+					self.pressed = candidate.pressed;
+					return Some(candidate);
+				}
+			}
+
+			// Bouncing back cancels the change, and a repeated edge keeps the later one
+			self.candidate = (edge.pressed != self.pressed).then_some(edge);
+		}
+
+		let candidate = self
+			.candidate
+			.filter(|candidate| self.is_settled(*candidate, now))?;
+		self.candidate = None;
+		self.pressed = candidate.pressed;
+
+		Some(candidate)
+	}
+}
+
+impl<K: KeyInput + Indicators> Indicators for Debounced<K> {
+	fn set(&mut self, led: Led, on: bool) { self.key.set(led, on) }
+}
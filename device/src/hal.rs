@@ -0,0 +1,97 @@
+/// The LEDs showing the state of the message
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Led {
+	/// Blinks the finished message
+	Internal,
+	Letter,
+	Word,
+	ShortPress,
+	LongPress,
+	PassageEnd,
+}
+
+impl Led {
+	pub const ALL: [Self; 6] = [
+		Self::Internal,
+		Self::Letter,
+		Self::Word,
+		Self::ShortPress,
+		Self::LongPress,
+		Self::PassageEnd,
+	];
+
+	/// The name the LED is shown with
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Internal => "internal_led",
+			Self::Letter => "letter_led",
+			Self::Word => "word_led",
+			Self::ShortPress => "short_press_led",
+			Self::LongPress => "long_press_led",
+			Self::PassageEnd => "passage_end_led",
+		}
+	}
+}
+
+/// The key being pressed or released
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+	/// Microseconds since the clock started
+	pub time:    u64,
+	pub pressed: bool,
+}
+
+/// The key the message is entered on, timestamping each time it is pressed or released
+pub trait KeyInput {
+	/// Takes the oldest press or release that has happened by a time
+	///
+	/// # Arguments
+	/// * `now` - Microseconds since the clock started
+	///
+	/// # Returns
+	/// * `Option<KeyEvent>` - The event, `None` if there are no more yet
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent>;
+}
+
+/// The LEDs the state of the message is shown on
+pub trait Indicators {
+	/// Turns an LED on or off
+	fn set(&mut self, led: Led, on: bool);
+
+	/// Turns every LED but the internal one off
+	fn leds_off(&mut self) {
+		self.set(Led::PassageEnd, false);
+		self.set(Led::Word, false);
+		self.set(Led::Letter, false);
+		self.set(Led::ShortPress, false);
+		self.set(Led::LongPress, false);
+	}
+}
+
+/// A clock that only counts up, used for waiting and timestamps
+pub trait Clock {
+	/// Microseconds since the clock started
+	fn now(&self) -> u64;
+
+	/// Waits for the given number of milliseconds
+	fn delay_ms(&mut self, ms: u32);
+}
+
+/// Somewhere text is written to, such as the USB serial port
+pub trait TextSink {
+	/// Writes bytes of UTF-8 text
+	fn write(&mut self, bytes: &[u8]);
+
+	/// Waits until everything written has been sent
+	fn flush(&mut self) {}
+}
+
+/// Somewhere text is read from, such as the USB serial port
+pub trait TextSource {
+	/// Reads any bytes that have been received into the buffer without waiting for more
+	///
+	/// # Returns
+	/// * `usize` - The number of bytes read
+	fn read(&mut self, buffer: &mut [u8]) -> usize;
+}
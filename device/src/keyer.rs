@@ -0,0 +1,453 @@
+//! A keyer, sending self-timed dots and dashes from a pair of paddles
+//!
+//! Holding the dit paddle sends a run of dots and holding the dah paddle a run of dashes.
+//! Squeezing both sends them alternately, starting with whichever was pressed first,
+//! or in Ultimatic mode repeats the element of the paddle pressed last.
+//! A paddle pressed while an element is being sent is remembered and sent next,
+//! even if it is let go before then. In bug mode the dah paddle keys by hand instead,
+//! as on a semi-automatic key.
+//!
+//! The [`Keyer`] is a key itself, pressing and releasing for each element it sends,
+//! so the message is read from it in the same way as from a straight key.
+
+use heapless::Deque;
+use morse::keying::Timing;
+
+use crate::{
+	button::PASSAGE_END_LENGTH,
+	hal::{Indicators, KeyEvent, KeyInput, Led},
+};
+
+/// Microseconds the paddles are left for to finish the message
+pub const IDLE_END_LENGTH: u64 = 5_000_000;
+
+/// End of the gap after a mark keyed by hand, which isn't known until the paddle is let go
+const MANUAL_END: u64 = u64::MAX;
+
+/// The two levers of a paddle key
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Paddle {
+	Dit,
+	Dah,
+}
+
+impl Paddle {
+	/// The other paddle
+	#[must_use]
+	pub const fn other(self) -> Self {
+		match self {
+			Self::Dit => Self::Dah,
+			Self::Dah => Self::Dit,
+		}
+	}
+}
+
+/// How the keyer sends from the paddles
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KeyerMode {
+	/// Alternates while squeezed, stopping after the element being sent when both are let go
+	IambicA,
+	/// Alternates while squeezed, sending one more element, opposite to the one being sent,
+	/// when both are let go
+	#[default]
+	IambicB,
+	/// Sends dots from the dit paddle, with the dah paddle held down for as long as the mark
+	Bug,
+	/// Repeats the element of the paddle pressed last while squeezed
+	Ultimatic,
+}
+
+impl KeyerMode {
+	pub const ALL: [Self; 4] = [Self::IambicA, Self::IambicB, Self::Bug, Self::Ultimatic];
+
+	/// The name the mode is selected by
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::IambicA => "iambic-a",
+			Self::IambicB => "iambic-b",
+			Self::Bug => "bug",
+			Self::Ultimatic => "ultimatic",
+		}
+	}
+}
+
+/// Sends elements from the paddles, showing them on the LEDs it passes on
+///
+/// After the paddles have been left for [`IDLE_END_LENGTH`] the key is held long enough
+/// to finish the message.
+pub struct Keyer<'a, D: KeyInput, H: KeyInput, I: Indicators> {
+	dit:          &'a mut D,
+	dah:          &'a mut H,
+	indicators:   &'a mut I,
+	mode:         KeyerMode,
+	/// Length of a dot in microseconds
+	unit:         u64,
+	/// Whether each paddle is held, indexed by [`Paddle`]
+	held:         [bool; 2],
+	/// Paddles pressed since the element being sent started, which are sent even if let go
+	memory:       [bool; 2],
+	/// Whether both paddles have been held at once since the element being sent started
+	squeezed:     bool,
+	/// The paddle pressed most recently
+	last_pressed: Paddle,
+	/// Edges taken from each paddle that haven't been reached yet
+	unread:       [Option<KeyEvent>; 2],
+	/// The element being sent, and the time the gap after it ends, [`MANUAL_END`] for a mark
+	/// keyed by hand
+	sending:      Option<(Paddle, u64)>,
+	/// Presses and releases of the key that haven't been taken yet
+	keyed:        Deque<KeyEvent, 4>,
+	/// Time the last element finished, once one has been sent
+	idle_since:   Option<u64>,
+}
+
+impl<'a, D: KeyInput, H: KeyInput, I: Indicators> Keyer<'a, D, H, I> {
+	/// # Arguments
+	/// * `dit` - The paddle sending dots
+	/// * `dah` - The paddle sending dashes
+	/// * `indicators` - The LEDs to pass on
+	/// * `mode` - How the paddles are sent from
+	/// * `timing` - The speed to send at
+	#[must_use]
+	pub fn new(
+		dit: &'a mut D,
+		dah: &'a mut H,
+		indicators: &'a mut I,
+		mode: KeyerMode,
+		timing: &Timing,
+	) -> Self {
+		Self {
+			dit,
+			dah,
+			indicators,
+			mode,
+			unit: u64::from(timing.unit()),
+			held: [false; 2],
+			memory: [false; 2],
+			squeezed: false,
+			last_pressed: Paddle::Dit,
+			unread: [None; 2],
+			sending: None,
+			keyed: Deque::new(),
+			idle_since: None,
+		}
+	}
+
+	/// Takes the oldest edge of either paddle that has happened by a time
+	fn next_edge(&mut self, now: u64) -> Option<(Paddle, KeyEvent)> {
+		if self.unread[Paddle::Dit as usize].is_none() {
+			self.unread[Paddle::Dit as usize] = self.dit.next_event(now);
+		}
+		if self.unread[Paddle::Dah as usize].is_none() {
+			self.unread[Paddle::Dah as usize] = self.dah.next_event(now);
+		}
+
+		let paddle = match self.unread {
+			[Some(dit), Some(dah)] if dah.time < dit.time => Paddle::Dah,
+			[Some(_), _] => Paddle::Dit,
+			[None, Some(_)] => Paddle::Dah,
+			[None, None] => return None,
+		};
+
+		self.unread[paddle as usize]
+			.take()
+			.map(|edge| (paddle, edge))
+	}
+
+	/// Puts an edge back to be reached later
+	const fn unread(&mut self, paddle: Paddle, edge: KeyEvent) {
+		self.unread[paddle as usize] = Some(edge);
+	}
+
+	/// Updates the paddles with an edge, starting an element if none is being sent
+	fn paddle_edge(&mut self, paddle: Paddle, edge: KeyEvent) {
+		self.held[paddle as usize] = edge.pressed;
+
+		if !edge.pressed {
+			if self.sending == Some((paddle, MANUAL_END)) {
+				self.key(edge.time, false);
+				self.sending = Some((paddle, edge.time + self.unit));
+			}
+			return;
+		}
+
+		self.last_pressed = paddle;
+
+		if self.held == [true; 2] {
+			self.squeezed = true;
+		}
+
+		if self.sending.is_some() {
+			// A bug's dah is only keyed while the paddle is held, so it can't be remembered
+			self.memory[paddle as usize] = self.mode != KeyerMode::Bug || paddle == Paddle::Dit;
+		} else {
+			self.start_element(paddle, edge.time);
+		}
+	}
+
+	/// Keys an element and the gap after it
+	fn start_element(&mut self, paddle: Paddle, time: u64) {
+		self.memory[paddle as usize] = false;
+
+		// A bug's dah is held down by hand, so it ends when the paddle is let go
+		if self.mode == KeyerMode::Bug && paddle == Paddle::Dah {
+			self.key(time, true);
+			self.sending = Some((paddle, MANUAL_END));
+			return;
+		}
+
+		let length = match paddle {
+			Paddle::Dit => self.unit,
+			Paddle::Dah => self.unit * 3,
+		};
+
+		self.key(time, true);
+		self.key(time + length, false);
+
+		self.sending = Some((paddle, time + length + self.unit));
+		self.squeezed = self.held == [true; 2];
+	}
+
+	/// Chooses what follows an element once its gap is over
+	fn finish_element(&mut self, last: Paddle, time: u64) {
+		self.sending = None;
+
+		let is_wanted = |paddle: Paddle| self.held[paddle as usize] || self.memory[paddle as usize];
+
+		let next = if self.mode == KeyerMode::Ultimatic && self.held == [true; 2] {
+			Some(self.last_pressed)
+		} else if is_wanted(last.other()) {
+			Some(last.other())
+		} else if is_wanted(last) {
+			Some(last)
+		} else if self.mode == KeyerMode::IambicB && self.squeezed {
+			Some(last.other())
+		} else {
+			None
+		};
+
+		match next {
+			Some(paddle) => self.start_element(paddle, time),
+			None => self.idle_since = Some(time),
+		}
+	}
+
+	fn key(&mut self, time: u64, pressed: bool) {
+		self.keyed
+			.push_back(KeyEvent { time, pressed })
+			.unwrap_or_default();
+	}
+}
+
+impl<D: KeyInput, H: KeyInput, I: Indicators> KeyInput for Keyer<'_, D, H, I> {
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent> {
+		loop {
+			if self.keyed.front().is_some_and(|event| event.time <= now) {
+				return self.keyed.pop_front();
+			}
+
+			// Paddle edges and the ends of elements are handled in the order they happened
+			let edge = self.next_edge(now);
+			let finished = self
+				.sending
+				.filter(|(_, end)| *end <= now && edge.is_none_or(|(_, edge)| edge.time >= *end));
+
+			match (finished, edge) {
+				(Some((last, end)), edge) => {
+					if let Some((paddle, edge)) = edge {
+						self.unread(paddle, edge);
+					}
+					self.finish_element(last, end);
+				}
+				(None, Some((paddle, edge))) => self.paddle_edge(paddle, edge),
+				(None, None) => break,
+			}
+		}
+
+		// Holds the key long enough to finish the message once the paddles have been left
+		let idle_since = self.idle_since?;
+		let hold_start = idle_since + IDLE_END_LENGTH;
+
+		if self.sending.is_none() && self.held == [false; 2] && now >= hold_start {
+			self.idle_since = None;
+			self.key(hold_start, true);
+			self.key(
+				hold_start + u64::from(PASSAGE_END_LENGTH) * 1000 + 200_000,
+				false,
+			);
+
+			return self.keyed.pop_front();
+		}
+
+		None
+	}
+}
+
+impl<D: KeyInput, H: KeyInput, I: Indicators> Indicators for Keyer<'_, D, H, I> {
+	fn set(&mut self, led: Led, on: bool) { self.indicators.set(led, on) }
+}
+
+#[cfg(test)]
+// A paddle pressed once is still a list of presses
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+	use std::{ops::Range, vec::Vec};
+
+	use super::*;
+	use crate::mock::{MockClock, MockPinSet};
+
+	const TIMING: Timing = Timing::new(20.0);
+
+	fn unit() -> u64 { u64::from(TIMING.unit()) }
+
+	/// Runs the keyer over the paddles' presses, polling every millisecond up to a time
+	///
+	/// # Returns
+	/// * `Vec<(u64, u64)>` - The start and length of each mark keyed, in microseconds
+	fn keyed(
+		mode: KeyerMode,
+		dit: &[Range<u64>],
+		dah: &[Range<u64>],
+		until: u64,
+	) -> Vec<(u64, u64)> {
+		let clock = MockClock::new();
+		let mut dit = MockPinSet::new(clock.clone(), dit.to_vec());
+		let mut dah = MockPinSet::new(clock.clone(), dah.to_vec());
+		let mut leds = MockPinSet::new(clock, Vec::new());
+		let mut keyer = Keyer::new(&mut dit, &mut dah, &mut leds, mode, &TIMING);
+
+		let mut marks = Vec::new();
+		let mut down = None;
+
+		for now in (0..until).step_by(1000) {
+			while let Some(event) = keyer.next_event(now) {
+				if event.pressed {
+					down = Some(event.time);
+				} else if let Some(start) = down.take() {
+					marks.push((start, event.time - start));
+				}
+			}
+		}
+
+		marks
+	}
+
+	/// A dot or dash at a time in units
+	fn dot(at: u64) -> (u64, u64) { (at * unit(), unit()) }
+
+	fn dash(at: u64) -> (u64, u64) { (at * unit(), unit() * 3) }
+
+	#[test]
+	fn holding_a_paddle_repeats_its_element() {
+		let u = unit();
+
+		for mode in KeyerMode::ALL {
+			assert_eq!(
+				keyed(mode, &[0..u * 4 + u / 6], &[], u * 20),
+				[dot(0), dot(2), dot(4)],
+				"{mode:?}"
+			);
+		}
+		for mode in [KeyerMode::IambicA, KeyerMode::IambicB, KeyerMode::Ultimatic] {
+			assert_eq!(
+				keyed(mode, &[], &[0..u * 4 + u / 6], u * 20),
+				[dash(0), dash(4)],
+				"{mode:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn remembers_a_paddle_tapped_during_an_element() {
+		let u = unit();
+
+		for mode in [KeyerMode::IambicA, KeyerMode::IambicB, KeyerMode::Ultimatic] {
+			assert_eq!(
+				keyed(mode, &[0..u / 2], &[u / 3..u * 2 / 3], u * 20),
+				[dot(0), dash(2)],
+				"{mode:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn iambic_b_sends_one_more_element_after_a_squeeze() {
+		let u = unit();
+		// Let go in the gap after the third element, and during the fourth
+		let squeeze = |mode, release| keyed(mode, &[u / 2..release], &[0..release], u * 30);
+
+		assert_eq!(
+			squeeze(KeyerMode::IambicA, u * 19 / 2),
+			[dash(0), dot(4), dash(6)]
+		);
+		assert_eq!(
+			squeeze(KeyerMode::IambicB, u * 19 / 2),
+			[dash(0), dot(4), dash(6), dot(10)]
+		);
+		assert_eq!(
+			squeeze(KeyerMode::IambicA, u * 21 / 2),
+			[dash(0), dot(4), dash(6), dot(10)]
+		);
+		assert_eq!(
+			squeeze(KeyerMode::IambicB, u * 21 / 2),
+			[dash(0), dot(4), dash(6), dot(10), dash(12)]
+		);
+	}
+
+	#[test]
+	fn ultimatic_repeats_the_paddle_pressed_last() {
+		let u = unit();
+		// The dit paddle is pressed during the first dash and let go during the second dot
+		let dit = [u / 2..u * 13 / 2];
+		let dah = [0..u * 9];
+
+		assert_eq!(
+			keyed(KeyerMode::Ultimatic, &dit, &dah, u * 30),
+			[dash(0), dot(4), dot(6), dash(8)]
+		);
+		assert_eq!(
+			keyed(KeyerMode::IambicB, &dit, &dah, u * 30),
+			[dash(0), dot(4), dash(6), dot(10)]
+		);
+	}
+
+	#[test]
+	fn bug_keys_the_dah_paddle_by_hand() {
+		let u = unit();
+		// Dots, a mark held for five units, then a dot tapped in the gap after it
+		let dit = [0..u * 4 + u / 6, u * 25 / 2..u * 127 / 10];
+		let dah = [u * 7..u * 12];
+
+		assert_eq!(
+			keyed(KeyerMode::Bug, &dit, &dah, u * 30),
+			[dot(0), dot(2), dot(4), (u * 7, u * 5), dot(13)]
+		);
+	}
+
+	#[test]
+	fn bug_forgets_the_dah_paddle_let_go_during_a_dot() {
+		let u = unit();
+
+		assert_eq!(
+			keyed(KeyerMode::Bug, &[0..u / 2], &[u / 4..u / 2], u * 20),
+			[dot(0)]
+		);
+	}
+
+	#[test]
+	fn finishes_the_message_once_the_paddles_are_left() {
+		let u = unit();
+		let marks = keyed(
+			KeyerMode::IambicB,
+			&[0..u / 2],
+			&[],
+			u * 2 + IDLE_END_LENGTH + 2_000_000,
+		);
+
+		assert_eq!(marks.len(), 2);
+		assert_eq!(marks[0], dot(0));
+		assert_eq!(marks[1].0, u * 2 + IDLE_END_LENGTH);
+		assert!(marks[1].1 > u64::from(PASSAGE_END_LENGTH) * 1000);
+	}
+}
@@ -0,0 +1,22 @@
+use morse::{
+	code::Code,
+	keying::{keying, Timing},
+};
+
+use crate::hal::{Clock, Indicators, Led};
+
+/// Blinks at 6 words per minute, a unit of 200 ms
+const TIMING: Timing = Timing::new(6.0);
+
+/// Blink led based on provided morse code
+///
+/// # Arguments
+/// * `indicators` - The LEDs to blink the internal LED of
+/// * `clock` - The system clock
+/// * `codes` - The morse code to blink, such as a message read or one built with [`morse::morse!`]
+pub fn blink_codes<I: Indicators, C: Clock>(indicators: &mut I, clock: &mut C, codes: &[Code]) {
+	for span in keying(codes.iter().copied(), &TIMING) {
+		indicators.set(Led::Internal, span.on);
+		clock.delay_ms(span.duration / 1000);
+	}
+}
@@ -0,0 +1,515 @@
+//! Button and serial modes written against small hardware traits,
+//! so they run on the Pico and against mocks on the host alike
+//!
+//! The crate is `no_std` unless the `std` feature is enabled, which adds the [`mock`] hardware.
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+pub mod button;
+pub mod debounce;
+pub mod hal;
+pub mod keyer;
+pub mod led;
+#[cfg(any(test, feature = "std"))]
+pub mod mock;
+pub mod serial;
+pub mod trace;
+
+use morse::{
+	alphabet::Table, code::Code, codes_to_string, keying::Timing, string_to_codes, BUFFER_LENGTH,
+};
+
+use crate::{
+	button::{scan, scan_american, START_TIMING},
+	hal::{Clock, Indicators, KeyEvent, KeyInput, TextSink, TextSource},
+	keyer::Keyer,
+	serial::{new_line, read, select_alphabet, select_keyer_mode, write_error},
+	trace::{read_trace, write_trace, Recorder, ReplayClock, ReplayPinSet},
+};
+
+/// Reads a message from the button and writes it as text to the serial port,
+/// followed by the trace of the key presses
+///
+/// # Arguments
+/// * `pin_set` - The button and LEDs
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code read, to be blinked with [`led::blink_codes`]
+pub fn button_mode<P, C, S>(pin_set: &mut P, clock: &mut C, serial: &mut S) -> [Code; BUFFER_LENGTH]
+where
+	P: KeyInput + Indicators,
+	C: Clock,
+	S: TextSink + TextSource,
+{
+	let alphabet = select_alphabet(serial);
+
+	serial.write(b"Please press the button to start your message\r\n");
+
+	let first_press = wait_for_press(pin_set, clock);
+
+	let mut recorder = Recorder::new();
+	let codes = scan_message(
+		pin_set,
+		clock,
+		serial,
+		alphabet,
+		&START_TIMING,
+		first_press,
+		&mut recorder,
+	);
+
+	write_text(serial, clock, &codes, alphabet);
+	write_trace(serial, &recorder);
+
+	codes
+}
+
+/// Reads a message from a pair of paddles through the keyer and writes it as text to the
+/// serial port, followed by the trace of the elements sent
+///
+/// # Arguments
+/// * `dit` - The paddle sending dots
+/// * `dah` - The paddle sending dashes
+/// * `indicators` - The LEDs
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+/// * `timing` - The speed the keyer sends at
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code read, to be blinked with [`led::blink_codes`]
+pub fn keyer_mode<D, H, I, C, S>(
+	dit: &mut D,
+	dah: &mut H,
+	indicators: &mut I,
+	clock: &mut C,
+	serial: &mut S,
+	timing: &Timing,
+) -> [Code; BUFFER_LENGTH]
+where
+	D: KeyInput,
+	H: KeyInput,
+	I: Indicators,
+	C: Clock,
+	S: TextSink + TextSource,
+{
+	let alphabet = select_alphabet(serial);
+	let mode = select_keyer_mode(serial);
+
+	serial.write(b"Please press a paddle to start your message\r\n");
+
+	let mut keyer = Keyer::new(dit, dah, indicators, mode, timing);
+
+	let first_press = wait_for_press(&mut keyer, clock);
+
+	let mut recorder = Recorder::new();
+	let codes = scan_message(
+		&mut keyer,
+		clock,
+		serial,
+		alphabet,
+		timing,
+		first_press,
+		&mut recorder,
+	);
+
+	write_text(serial, clock, &codes, alphabet);
+	write_trace(serial, &recorder);
+
+	codes
+}
+
+/// Reads a key trace from the serial port and replays it through button mode,
+/// writing the message as text to the serial port
+///
+/// The trace is replayed as fast as it can be decoded rather than in real time.
+///
+/// # Arguments
+/// * `indicators` - The LEDs to show the replay on
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code read, to be blinked with [`led::blink_codes`]
+pub fn replay_mode<I, C, S>(
+	indicators: &mut I,
+	clock: &mut C,
+	serial: &mut S,
+) -> [Code; BUFFER_LENGTH]
+where
+	I: Indicators,
+	C: Clock,
+	S: TextSink + TextSource,
+{
+	let alphabet = select_alphabet(serial);
+
+	serial.write(b"Please paste the key trace, finishing with a line reading end.\r\n");
+
+	let events = read_trace(serial);
+
+	// Starts from the first press, as button mode waits for it
+	let start = events.first().map_or(0, |event| event.time);
+
+	replay_trace(
+		&events,
+		indicators,
+		&mut ReplayClock::new(start),
+		clock,
+		serial,
+		alphabet,
+	)
+}
+
+/// Replays a trace through button mode, writing the message as text to the serial port
+///
+/// After the last event the key is held long enough to finish the message,
+/// in case the trace was cut short before its final hold.
+///
+/// # Arguments
+/// * `events` - The trace, in time order
+/// * `indicators` - The LEDs to show the replay on
+/// * `replay_clock` - The clock the trace is replayed on, starting from the first event
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+/// * `alphabet` - The alphabet to read the message with
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code read
+pub fn replay_trace<I, R, C, S>(
+	events: &[KeyEvent],
+	indicators: &mut I,
+	replay_clock: &mut R,
+	clock: &mut C,
+	serial: &mut S,
+	alphabet: Table,
+) -> [Code; BUFFER_LENGTH]
+where
+	I: Indicators,
+	R: Clock,
+	C: Clock,
+	S: TextSink,
+{
+	let mut replay_pin_set = ReplayPinSet::new(events, indicators);
+
+	let first_press = wait_for_press(&mut replay_pin_set, replay_clock);
+
+	let codes = scan_message(
+		&mut replay_pin_set,
+		replay_clock,
+		serial,
+		alphabet,
+		&START_TIMING,
+		first_press,
+		&mut Recorder::new(),
+	);
+
+	write_text(serial, clock, &codes, alphabet);
+
+	codes
+}
+
+/// Reads text from the serial port and writes it back as morse code
+///
+/// # Arguments
+/// * `clock` - The system clock
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `[Code; BUFFER_LENGTH]` - The morse code of the text, to be blinked with [`led::blink_codes`]
+pub fn serial_mode<C, S>(clock: &mut C, serial: &mut S) -> [Code; BUFFER_LENGTH]
+where
+	C: Clock,
+	S: TextSink + TextSource,
+{
+	let alphabet = select_alphabet(serial);
+
+	// Asks again until the text can be encoded
+	let codes = loop {
+		serial.write(b"Please enter the text you wish to encode into morse.\r\n");
+
+		let converted_text = read(serial);
+
+		match string_to_codes(&converted_text, &alphabet) {
+			Ok(codes) => break codes,
+			Err(error) => write_error(serial, &error),
+		}
+	};
+
+	clock.delay_ms(1);
+
+	for code in codes {
+		if code != Code::None {
+			serial.write(code.to_marks().as_bytes());
+			clock.delay_ms(1);
+			serial.write(b" ");
+			clock.delay_ms(1);
+		}
+	}
+
+	serial.flush();
+
+	new_line(serial, clock);
+
+	codes
+}
+
+/// Waits for the key to be pressed, skipping any releases before it
+fn wait_for_press<K: KeyInput, C: Clock>(key: &mut K, clock: &mut C) -> KeyEvent {
+	loop {
+		while let Some(event) = key.next_event(clock.now()) {
+			if event.pressed {
+				return event;
+			}
+		}
+
+		clock.delay_ms(1);
+	}
+}
+
+/// Scans the button with the timings of the alphabet, learning the speed from a starting timing
+fn scan_message<P, C, S>(
+	pin_set: &mut P,
+	clock: &mut C,
+	serial: &mut S,
+	alphabet: Table,
+	timing: &Timing,
+	first_press: KeyEvent,
+	recorder: &mut Recorder,
+) -> [Code; BUFFER_LENGTH]
+where
+	P: KeyInput + Indicators,
+	C: Clock,
+	S: TextSink,
+{
+	if alphabet == Table::American {
+		scan_american(pin_set, clock, serial, first_press, recorder)
+	} else {
+		scan(
+			pin_set,
+			clock,
+			serial,
+			alphabet,
+			timing,
+			first_press,
+			recorder,
+		)
+	}
+}
+
+/// Writes a scanned message as text on its own line
+fn write_text<S: TextSink, C: Clock>(
+	serial: &mut S,
+	clock: &mut C,
+	codes: &[Code; BUFFER_LENGTH],
+	alphabet: Table,
+) {
+	new_line(serial, clock);
+
+	match codes_to_string(codes, &alphabet) {
+		Ok(string) => {
+			serial.write(string.as_bytes());
+		}
+		Err(error) => write_error(serial, &error),
+	}
+
+	new_line(serial, clock);
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{ops::Range, vec::Vec};
+
+	use morse::{code::Code, keying::keying, timing::Span};
+
+	use super::*;
+	use crate::{
+		hal::Led,
+		mock::{MockClock, MockPinSet, MockSerial},
+	};
+
+	/// The times a key is held down for to key spans, starting a second in and finishing
+	/// with a hold long enough to end the message
+	fn presses<I: IntoIterator<Item = Span>>(spans: I) -> Vec<Range<u64>> {
+		let mut presses = Vec::new();
+		let mut time = 1_000_000;
+
+		for span in spans {
+			let end = time + u64::from(span.duration);
+			if span.on {
+				presses.push(time..end);
+			}
+			time = end;
+		}
+
+		let start = time + 500_000;
+		presses.push(start..start + 2_000_000);
+
+		presses
+	}
+
+	fn text_codes(text: &str) -> [Code; BUFFER_LENGTH] {
+		string_to_codes(&text.into(), &Table::default()).unwrap()
+	}
+
+	/// Runs button mode with the default alphabet
+	fn run_button_mode(presses: Vec<Range<u64>>) -> (MockPinSet, MockSerial) {
+		let mut clock = MockClock::new();
+		let mut pin_set = MockPinSet::new(clock.clone(), presses);
+		let mut serial = MockSerial::new("\r");
+
+		button_mode(&mut pin_set, &mut clock, &mut serial);
+
+		(pin_set, serial)
+	}
+
+	/// Runs serial mode with the default alphabet and the lines typed after it
+	fn run_serial_mode(input: &str) -> (MockSerial, [Code; BUFFER_LENGTH]) {
+		let mut serial = MockSerial::new(&format!("\r{input}"));
+		let codes = serial_mode(&mut MockClock::new(), &mut serial);
+
+		(serial, codes)
+	}
+
+	#[test]
+	fn button_mode_reads_paris() {
+		let presses = presses(keying(text_codes("paris"), &Timing::new(12.0)));
+		let first_press = presses[0].start;
+		let (pin_set, serial) = run_button_mode(presses);
+		let output = serial.output();
+
+		assert!(output.contains(".--. .- .-. .. ... "), "{output}");
+		assert!(output.contains("\n\rparis \n\r"), "{output}");
+		assert!(output.contains("# Key trace"), "{output}");
+
+		let turned_on = |led: Led| {
+			pin_set
+				.transitions
+				.iter()
+				.filter(|transition| transition.led == led && transition.on)
+				.count()
+		};
+
+		// Every press lights the short press LED, and the dashes of p, a and r and the final hold
+		// the long press one
+		assert_eq!(turned_on(Led::ShortPress), 15);
+		assert_eq!(turned_on(Led::LongPress), 5);
+		assert_eq!(turned_on(Led::Letter), 5);
+		assert_eq!(turned_on(Led::PassageEnd), 1);
+		assert!(pin_set.transitions[0].time >= first_press);
+		assert!(Led::ALL.iter().all(|led| !pin_set.is_on(*led)));
+	}
+
+	#[test]
+	fn button_mode_reports_a_message_too_long_for_the_buffer() {
+		let codes = [Code::letter("."); BUFFER_LENGTH + 6];
+		let (_, serial) = run_button_mode(presses(keying(codes, &Timing::new(12.0))));
+		let output = serial.output();
+
+		assert!(
+			output.contains("Error: Message is too long for the buffer."),
+			"{output}"
+		);
+		assert!(output.contains(&"e".repeat(BUFFER_LENGTH)), "{output}");
+	}
+
+	#[test]
+	fn button_mode_learns_long_gaps_between_words() {
+		let timing = Timing::new(12.0);
+		let unit = timing.unit();
+		let mut spans = Vec::new();
+
+		// Gaps between words twice as long as standard, and a slow gap inside the last word
+		// that is still far shorter than them
+		let words = ["cq", "cq", "cq", "de", "m0abc", "m0abc", "test", "a", "b"];
+		let gaps = [14, 14, 14, 14, 14, 14, 14, 8, 14];
+
+		for (word, gap) in words.into_iter().zip(gaps) {
+			spans.extend(keying(text_codes(word), &timing));
+			spans.last_mut().unwrap().duration = unit * gap;
+		}
+
+		let (_, serial) = run_button_mode(presses(spans));
+		let output = serial.output();
+
+		assert!(
+			output.contains("\n\rcq cq cq de m0abc m0abc test ab \n\r"),
+			"{output}"
+		);
+	}
+
+	#[test]
+	fn button_mode_reads_prosigns_keyed_on_their_own() {
+		let presses = presses(keying(
+			text_codes("cq cq <bt> de m0abc 2+2 <ar>"),
+			&Timing::new(12.0),
+		));
+		let (_, serial) = run_button_mode(presses);
+		let output = serial.output();
+
+		assert!(
+			output.contains("\n\rcq cq <BT> de m0abc 2+2 <AR> \n\r"),
+			"{output}"
+		);
+	}
+
+	#[test]
+	fn button_mode_echoes_the_marks_a_letter_was_read_as() {
+		// The first dash is shorter than a dash at the starting speed, until the speed is learnt
+		let presses = presses(keying(text_codes("cq"), &Timing::new(12.0)));
+		let (_, serial) = run_button_mode(presses);
+		let output = serial.output();
+
+		assert!(output.contains("\n-.-. --.- "), "{output}");
+		assert!(output.contains("\n\rcq \n\r"), "{output}");
+	}
+
+	#[test]
+	fn serial_mode_encodes_paris() {
+		let (serial, codes) = run_serial_mode("paris\r");
+		let output = serial.output();
+
+		assert!(
+			output.contains("Now encoding 'paris' to morse."),
+			"{output}"
+		);
+		assert!(output.contains(".--. .- .-. .. ... "), "{output}");
+		assert_eq!(codes, text_codes("paris"));
+	}
+
+	#[test]
+	fn serial_mode_asks_again_after_an_unknown_character() {
+		let (serial, codes) = run_serial_mode("pa%is\rparis\r");
+		let output = serial.output();
+
+		assert!(
+			output.contains("Error: Unknown character '%' at position 2."),
+			"{output}"
+		);
+		assert_eq!(
+			output
+				.matches("Please enter the text you wish to encode into morse.")
+				.count(),
+			2
+		);
+		assert_eq!(codes, text_codes("paris"));
+	}
+
+	#[test]
+	fn serial_mode_cuts_a_line_too_long_for_the_buffer() {
+		let text = "e".repeat(BUFFER_LENGTH + 6);
+		let (serial, codes) = run_serial_mode(&format!("{text}\r"));
+		let output = serial.output();
+
+		assert!(output.contains("Buffer length reached."), "{output}");
+		assert!(
+			output.contains(&format!(
+				"Now encoding '{}' to morse.",
+				&text[..BUFFER_LENGTH]
+			)),
+			"{output}"
+		);
+		assert_eq!(codes, [Code::letter("."); BUFFER_LENGTH]);
+	}
+}
@@ -0,0 +1,156 @@
+//! In-memory hardware for running the modes on the host with scripted input
+
+use std::{borrow::Cow, cell::Cell, collections::VecDeque, ops::Range, rc::Rc};
+
+use crate::hal::{Clock, Indicators, KeyEvent, KeyInput, Led, TextSink, TextSource};
+
+/// A clock that only moves forward when waited on, clones share the same time
+#[derive(Clone, Debug, Default)]
+pub struct MockClock {
+	now: Rc<Cell<u64>>,
+}
+
+impl MockClock {
+	#[must_use]
+	pub fn new() -> Self { Self::default() }
+
+	/// # Arguments
+	/// * `start` - The time in microseconds to start from
+	#[must_use]
+	pub fn starting_at(start: u64) -> Self {
+		Self {
+			now: Rc::new(Cell::new(start)),
+		}
+	}
+}
+
+impl Clock for MockClock {
+	fn now(&self) -> u64 { self.now.get() }
+
+	fn delay_ms(&mut self, ms: u32) { self.now.set(self.now.get() + u64::from(ms) * 1000) }
+}
+
+/// A change of an LED, at a time in microseconds
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+	pub time: u64,
+	pub led:  Led,
+	pub on:   bool,
+}
+
+/// A key pressed over scripted times, and LEDs recording every time they change
+#[derive(Debug)]
+pub struct MockPinSet {
+	clock:           MockClock,
+	presses:         Vec<Range<u64>>,
+	/// Index of the next press or release to take
+	next:            usize,
+	states:          [bool; Led::ALL.len()],
+	pub transitions: Vec<Transition>,
+}
+
+impl MockPinSet {
+	/// # Arguments
+	/// * `clock` - The clock the LED changes are timed by
+	/// * `presses` - The times in microseconds the key is held down for, in order
+	#[must_use]
+	pub const fn new(clock: MockClock, presses: Vec<Range<u64>>) -> Self {
+		Self {
+			clock,
+			presses,
+			next: 0,
+			states: [false; Led::ALL.len()],
+			transitions: Vec::new(),
+		}
+	}
+
+	/// Whether an LED is currently on
+	#[must_use]
+	pub const fn is_on(&self, led: Led) -> bool { self.states[led as usize] }
+}
+
+impl KeyInput for MockPinSet {
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent> {
+		let event = self
+			.presses
+			.iter()
+			.flat_map(|press| {
+				[
+					KeyEvent {
+						time:    press.start,
+						pressed: true,
+					},
+					KeyEvent {
+						time:    press.end,
+						pressed: false,
+					},
+				]
+			})
+			.nth(self.next)
+			.filter(|event| event.time <= now)?;
+		self.next += 1;
+
+		Some(event)
+	}
+}
+
+impl Indicators for MockPinSet {
+	fn set(&mut self, led: Led, on: bool) {
+		if self.states[led as usize] != on {
+			self.states[led as usize] = on;
+			self.transitions.push(Transition {
+				time: self.clock.now(),
+				led,
+				on,
+			});
+		}
+	}
+}
+
+/// A serial port reading from scripted input and keeping everything written
+#[derive(Debug, Default)]
+pub struct MockSerial {
+	input:      VecDeque<u8>,
+	pub output: Vec<u8>,
+}
+
+impl MockSerial {
+	/// # Arguments
+	/// * `input` - The text received over the serial port
+	#[must_use]
+	pub fn new(input: &str) -> Self {
+		Self {
+			input:  input.bytes().collect(),
+			output: Vec::new(),
+		}
+	}
+
+	/// Everything written so far as text
+	#[must_use]
+	pub fn output(&self) -> Cow<'_, str> { String::from_utf8_lossy(&self.output) }
+}
+
+impl TextSink for MockSerial {
+	fn write(&mut self, bytes: &[u8]) { self.output.extend_from_slice(bytes) }
+}
+
+impl TextSource for MockSerial {
+	/// Reads up to the end of the next line, as if each line was typed separately
+	///
+	/// # Panics
+	/// * If all of the input has been read, as nothing more will arrive
+	fn read(&mut self, buffer: &mut [u8]) -> usize {
+		assert!(!self.input.is_empty(), "serial input has run out");
+
+		let line_length = self
+			.input
+			.iter()
+			.position(|byte| *byte == b'\n' || *byte == b'\r')
+			.map_or(self.input.len(), |index| index + 1);
+		let count = buffer.len().min(line_length);
+		for (byte, input) in buffer.iter_mut().zip(self.input.drain(..count)) {
+			*byte = input;
+		}
+		count
+	}
+}
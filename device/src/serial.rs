@@ -0,0 +1,231 @@
+use core::fmt::Write;
+
+use heapless::String;
+use morse::{alphabet::Table, error::Error, BUFFER_LENGTH};
+
+use crate::{
+	hal::{Clock, TextSink, TextSource},
+	keyer::KeyerMode,
+};
+
+/// Reads from the serial port and returns the string
+///
+/// # Arguments
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `String<BUFFER_LENGTH>` - The string read from the serial port
+pub fn read<S: TextSink + TextSource>(serial: &mut S) -> String<BUFFER_LENGTH> {
+	let mut buffer = [0u8; BUFFER_LENGTH];
+	let buffer_index = read_line(serial, &mut buffer);
+
+	if buffer.len() == buffer_index {
+		create_return_string("Buffer length reached.", buffer, serial)
+	} else {
+		create_return_string("Message submitted.", buffer, serial)
+	}
+}
+
+/// Asks for an alphabet over the serial port, keeping the default on an empty or unknown answer
+///
+/// # Arguments
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `Table` - The selected alphabet
+pub fn select_alphabet<S: TextSink + TextSource>(serial: &mut S) -> Table {
+	select(serial, "an alphabet", "alphabet", &Table::ALL, Table::name)
+}
+
+/// Asks for a keyer mode over the serial port, keeping the default on an empty or unknown answer
+///
+/// # Arguments
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `KeyerMode` - The selected keyer mode
+pub fn select_keyer_mode<S: TextSink + TextSource>(serial: &mut S) -> KeyerMode {
+	select(
+		serial,
+		"a keyer mode",
+		"keyer mode",
+		&KeyerMode::ALL,
+		KeyerMode::name,
+	)
+}
+
+/// Asks for one of a list of options by name, keeping the default on an empty or unknown answer
+///
+/// # Arguments
+/// * `serial` - The serial port
+/// * `prompt` - What is asked for, such as "an alphabet"
+/// * `kind` - What the options are, such as "alphabet"
+/// * `options` - The options to choose from
+/// * `name` - The name each option is selected by
+///
+/// # Returns
+/// * `T` - The selected option
+///
+/// # Panics
+/// * If the prompt doesn't fit in its buffer
+fn select<S, T>(
+	serial: &mut S,
+	prompt: &str,
+	kind: &str,
+	options: &[T],
+	name: fn(T) -> &'static str,
+) -> T
+where
+	S: TextSink + TextSource,
+	T: Copy + Default,
+{
+	let mut names = String::<{ BUFFER_LENGTH * 2 }>::new();
+
+	for option in options {
+		if !names.is_empty() {
+			names.push_str(", ").unwrap();
+		}
+		names.push_str(name(*option)).unwrap();
+	}
+
+	let mut formatted_message = String::<{ BUFFER_LENGTH * 3 }>::new();
+
+	write!(
+		&mut formatted_message,
+		"Enter {prompt} ({names}) or press return for {}.\r\n",
+		name(T::default())
+	)
+	.unwrap();
+
+	serial.write(formatted_message.as_bytes());
+
+	let mut buffer = [0u8; BUFFER_LENGTH];
+	let buffer_index = read_line(serial, &mut buffer);
+
+	let answer = core::str::from_utf8(&buffer[..buffer_index])
+		.unwrap_or_default()
+		.trim();
+	let selected = options
+		.iter()
+		.copied()
+		.find(|option| name(*option).eq_ignore_ascii_case(answer))
+		.unwrap_or_default();
+
+	formatted_message.clear();
+
+	write!(
+		&mut formatted_message,
+		"\r\nUsing the {} {kind}.\r\n",
+		name(selected)
+	)
+	.unwrap();
+
+	serial.write(formatted_message.as_bytes());
+
+	selected
+}
+
+/// Writes an error to the serial port on its own line
+///
+/// # Arguments
+/// * `serial` - The serial port
+/// * `error` - The error to write
+///
+/// # Panics
+/// * If the error message doesn't fit in its buffer
+pub fn write_error<S: TextSink>(serial: &mut S, error: &Error) {
+	let mut formatted_message = String::<{ BUFFER_LENGTH * 2 }>::new();
+
+	write!(&mut formatted_message, "\r\nError: {error}.\r\n").unwrap();
+
+	serial.write(formatted_message.as_bytes());
+}
+
+/// Writes a line ending to the serial port
+///
+/// # Arguments
+/// * `serial` - The serial port
+/// * `clock` - The system clock
+pub fn new_line<S: TextSink, C: Clock>(serial: &mut S, clock: &mut C) {
+	// Serial often needs delay beforehand or else it doesn't write properly ¯\_(ツ)_/¯
+	clock.delay_ms(1);
+	serial.write(b"\n\r");
+}
+
+/// Reads from the serial port into the buffer until it is full or a line ending is received
+///
+/// # Arguments
+/// * `serial` - The serial port
+/// * `buffer` - The buffer to read into
+///
+/// # Returns
+/// * `usize` - The number of bytes read
+fn read_line<S: TextSink + TextSource>(serial: &mut S, buffer: &mut [u8; BUFFER_LENGTH]) -> usize {
+	let mut buffer_index = 0;
+	loop {
+		let mut current_buffer = [0u8; BUFFER_LENGTH];
+		let count = serial
+			.read(&mut current_buffer)
+			.min(BUFFER_LENGTH - buffer_index);
+
+		if count > 0 {
+			buffer[buffer_index..(count + buffer_index)].copy_from_slice(&current_buffer[..count]);
+
+			buffer_index += count;
+
+			// Send back to the host
+			serial.write(&current_buffer[..count]);
+		}
+		if buffer.len() == buffer_index || buffer.contains(&b'\n') || buffer.contains(&b'\r') {
+			return buffer_index;
+		}
+	}
+}
+
+/// Creates the return string for the serial port & writes a message to the serial port
+///
+/// # Arguments
+/// * `message` - The message to write
+/// * `buffer` - The buffer to convert to a string
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `String<BUFFER_LENGTH>` - The string to return
+fn create_return_string<S: TextSink>(
+	message: &str,
+	mut buffer: [u8; BUFFER_LENGTH],
+	serial: &mut S,
+) -> String<BUFFER_LENGTH> {
+	let mut string = String::<BUFFER_LENGTH>::new();
+
+	for byte in &mut buffer {
+		// Remove linefeed and carriage return
+		if byte == &b'\n' || byte == &b'\r' {
+			*byte = 0;
+		}
+	}
+
+	// Drops a character cut in half by the end of the buffer
+	let text = match core::str::from_utf8(&buffer) {
+		Ok(text) => text,
+		Err(error) => core::str::from_utf8(&buffer[..error.valid_up_to()]).unwrap_or_default(),
+	};
+
+	// Add to string
+	for char in text.chars().filter(|char| *char != '\0') {
+		string.push(char).unwrap();
+	}
+
+	let mut formatted_message = String::<{ BUFFER_LENGTH * 2 }>::new();
+
+	write!(
+		&mut formatted_message,
+		"\r\n{message}\r\nNow encoding '{string}' to morse.\r\n",
+	)
+	.unwrap();
+
+	serial.flush();
+
+	serial.write(formatted_message.as_bytes());
+	string
+}
@@ -0,0 +1,286 @@
+//! Key press traces, written as text with one event per line
+//!
+//! Each event is the time in microseconds followed by `down` or `up`,
+//! lines starting with `#` are comments and blank lines are skipped.
+//! Over the serial port a trace finishes with a line reading `end`:
+//!
+//! ```text
+//! # a
+//! 1000000 down
+//! 1150000 up
+//! 1400000 down
+//! 2100000 up
+//! end
+//! ```
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use heapless::{String, Vec};
+use morse::BUFFER_LENGTH;
+
+use crate::{
+	button::PASSAGE_END_LENGTH,
+	hal::{Clock, Indicators, KeyEvent, KeyInput, Led, TextSink, TextSource},
+};
+
+/// Maximum number of events a trace can hold, later events are dropped
+pub const TRACE_LENGTH: usize = 512;
+
+impl Display for KeyEvent {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} {}",
+			self.time,
+			if self.pressed { "down" } else { "up" }
+		)
+	}
+}
+
+/// A line that isn't blank, a comment or an event
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidLine;
+
+impl Display for InvalidLine {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "Expected a time in microseconds followed by down or up")
+	}
+}
+
+/// Parses a line of a trace
+///
+/// # Returns
+/// * `Option<KeyEvent>` - The event on the line, `None` for blank lines and comments
+///
+/// # Errors
+/// * [`InvalidLine`] - If the line isn't blank, a comment or an event
+pub fn parse_line(line: &str) -> Result<Option<KeyEvent>, InvalidLine> {
+	let line = line.trim();
+
+	if line.is_empty() || line.starts_with('#') {
+		return Ok(None);
+	}
+
+	let mut words = line.split_whitespace();
+
+	let time = words
+		.next()
+		.and_then(|time| time.parse().ok())
+		.ok_or(InvalidLine)?;
+
+	let pressed = match words.next() {
+		Some("down") => true,
+		Some("up") => false,
+		_ => return Err(InvalidLine),
+	};
+
+	if words.next().is_some() {
+		return Err(InvalidLine);
+	}
+
+	Ok(Some(KeyEvent { time, pressed }))
+}
+
+/// Records every time the key changes between up and down
+#[derive(Debug, Default)]
+pub struct Recorder {
+	events:  Vec<KeyEvent, TRACE_LENGTH>,
+	pressed: bool,
+	dropped: bool,
+}
+
+impl Recorder {
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			events:  Vec::new(),
+			pressed: false,
+			dropped: false,
+		}
+	}
+
+	/// Records the state of the key, adding an event only when it has changed
+	///
+	/// # Arguments
+	/// * `time` - Microseconds since the clock started
+	/// * `pressed` - Whether the key is down
+	pub fn record(&mut self, time: u64, pressed: bool) {
+		if pressed != self.pressed {
+			self.pressed = pressed;
+			if self.events.push(KeyEvent { time, pressed }).is_err() {
+				self.dropped = true;
+			}
+		}
+	}
+
+	/// The events recorded so far
+	#[must_use]
+	pub fn events(&self) -> &[KeyEvent] { &self.events }
+
+	/// Whether events were dropped because the trace was full
+	#[must_use]
+	pub const fn dropped(&self) -> bool { self.dropped }
+}
+
+/// Writes a recorded trace, finishing with `end` so it can be sent back for replay
+///
+/// # Arguments
+/// * `serial` - The serial port
+/// * `recorder` - The recorded trace
+///
+/// # Panics
+/// * If an event doesn't fit in its line buffer
+pub fn write_trace<S: TextSink>(serial: &mut S, recorder: &Recorder) {
+	serial.write(b"# Key trace, times in microseconds\r\n");
+
+	if recorder.dropped() {
+		serial.write(b"# The trace is full, later events were dropped\r\n");
+	}
+
+	let mut line = String::<32>::new();
+
+	for event in recorder.events() {
+		line.clear();
+		write!(&mut line, "{event}\r\n").unwrap();
+		serial.write(line.as_bytes());
+	}
+
+	serial.write(b"end\r\n");
+}
+
+/// Reads a trace from the serial port up to a line reading `end`
+///
+/// Lines that aren't events, and events out of time order, are reported and skipped.
+///
+/// # Arguments
+/// * `serial` - The serial port
+///
+/// # Returns
+/// * `Vec<KeyEvent, TRACE_LENGTH>` - The events read, stopping at [`TRACE_LENGTH`]
+///
+/// # Panics
+/// * If an error message doesn't fit in its buffer
+pub fn read_trace<S: TextSink + TextSource>(serial: &mut S) -> Vec<KeyEvent, TRACE_LENGTH> {
+	let mut events: Vec<KeyEvent, TRACE_LENGTH> = Vec::new();
+	let mut line: Vec<u8, BUFFER_LENGTH> = Vec::new();
+	let mut number = 0;
+
+	loop {
+		let mut buffer = [0u8; BUFFER_LENGTH];
+		let count = serial.read(&mut buffer);
+
+		// A pasted trace arrives with several lines at once
+		for byte in &buffer[..count] {
+			if *byte != b'\n' && *byte != b'\r' {
+				// Lines too long for the buffer are cut short, and then reported as invalid
+				line.push(*byte).unwrap_or_default();
+				continue;
+			}
+
+			let text = core::str::from_utf8(&line).unwrap_or_default().trim();
+
+			if text == "end" {
+				return events;
+			}
+
+			if !text.is_empty() {
+				number += 1;
+			}
+
+			let error = match parse_line(text) {
+				Ok(Some(event)) if events.last().is_some_and(|last| event.time < last.time) => {
+					Some("Events must be in time order")
+				}
+				Ok(Some(event)) => events
+					.push(event)
+					.err()
+					.map(|_| "The trace is full, later events are dropped"),
+				Ok(None) => None,
+				Err(_) => Some("Expected a time in microseconds followed by down or up"),
+			};
+
+			if let Some(error) = error {
+				let mut message = String::<{ BUFFER_LENGTH * 2 }>::new();
+				write!(&mut message, "\r\nError: Line {number}: {error}.\r\n").unwrap();
+				serial.write(message.as_bytes());
+			}
+
+			line.clear();
+		}
+	}
+}
+
+/// A clock for replaying a trace, which moves forward when waited on instead of waiting
+pub struct ReplayClock {
+	now: u64,
+}
+
+impl ReplayClock {
+	/// # Arguments
+	/// * `start` - The time in microseconds to start from
+	#[must_use]
+	pub const fn new(start: u64) -> Self { Self { now: start } }
+}
+
+impl Clock for ReplayClock {
+	fn now(&self) -> u64 { self.now }
+
+	fn delay_ms(&mut self, ms: u32) { self.now += u64::from(ms) * 1000 }
+}
+
+/// A key pressed as in a trace, showing the replay on the real LEDs
+///
+/// After the trace the key is held long enough to finish the message,
+/// in case the trace was cut short before its final hold.
+pub struct ReplayPinSet<'a, I: Indicators> {
+	events:     &'a [KeyEvent],
+	/// Index of the next event to take, counting the final hold after the trace
+	next:       usize,
+	indicators: &'a mut I,
+}
+
+impl<'a, I: Indicators> ReplayPinSet<'a, I> {
+	/// # Arguments
+	/// * `events` - The trace, in time order
+	/// * `indicators` - The LEDs to show the replay on
+	#[must_use]
+	pub const fn new(events: &'a [KeyEvent], indicators: &'a mut I) -> Self {
+		Self {
+			events,
+			next: 0,
+			indicators,
+		}
+	}
+}
+
+impl<I: Indicators> KeyInput for ReplayPinSet<'_, I> {
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent> {
+		let passage_end = u64::from(PASSAGE_END_LENGTH) * 1000;
+		let end = self.events.last().map_or(0, |event| event.time) + passage_end;
+		// Long enough to be seen as the end of the message
+		let hold = [
+			KeyEvent {
+				time:    end,
+				pressed: true,
+			},
+			KeyEvent {
+				time:    end + passage_end + 200_000,
+				pressed: false,
+			},
+		];
+
+		let event = self
+			.events
+			.iter()
+			.chain(&hold)
+			.nth(self.next)
+			.filter(|event| event.time <= now)?;
+		self.next += 1;
+
+		Some(*event)
+	}
+}
+
+impl<I: Indicators> Indicators for ReplayPinSet<'_, I> {
+	fn set(&mut self, led: Led, on: bool) { self.indicators.set(led, on) }
+}
@@ -0,0 +1,65 @@
+use cortex_m::delay::Delay;
+use device::hal::{Clock, TextSink, TextSource};
+use rp2040_hal::{usb::UsbBus, Timer};
+use usb_device::device::UsbDevice;
+use usbd_serial::SerialPort;
+
+/// The system delay for waiting, and the timer for timestamps
+pub struct SystemClock {
+	delay: Delay,
+	timer: Timer,
+}
+
+impl SystemClock {
+	#[must_use]
+	pub const fn new(delay: Delay, timer: Timer) -> Self { Self { delay, timer } }
+}
+
+impl Clock for SystemClock {
+	fn now(&self) -> u64 { self.timer.get_counter().ticks() }
+
+	fn delay_ms(&mut self, ms: u32) { self.delay.delay_ms(ms) }
+}
+
+/// The USB serial port along with the device polling it
+pub struct UsbSerial<'a> {
+	serial:  SerialPort<'a, UsbBus>,
+	usb_dev: UsbDevice<'a, UsbBus>,
+}
+
+impl<'a> UsbSerial<'a> {
+	#[must_use]
+	pub const fn new(serial: SerialPort<'a, UsbBus>, usb_dev: UsbDevice<'a, UsbBus>) -> Self {
+		Self { serial, usb_dev }
+	}
+
+	/// Polls the USB device, returning whether there may be data to read
+	pub fn poll(&mut self) -> bool { self.usb_dev.poll(&mut [&mut self.serial]) }
+}
+
+impl TextSink for UsbSerial<'_> {
+	fn write(&mut self, bytes: &[u8]) {
+		let mut wr_ptr = bytes;
+		while !wr_ptr.is_empty() {
+			match self.serial.write(wr_ptr) {
+				Ok(len) => wr_ptr = &wr_ptr[len..],
+				// On error, just drop unwritten data.
+				// One possible error is Err(WouldBlock), meaning the USB
+				// write buffer is full.
+				Err(_) => break,
+			};
+		}
+	}
+
+	fn flush(&mut self) { self.serial.flush().unwrap() }
+}
+
+impl TextSource for UsbSerial<'_> {
+	fn read(&mut self, buffer: &mut [u8]) -> usize {
+		if self.poll() {
+			self.serial.read(buffer).unwrap_or(0)
+		} else {
+			0
+		}
+	}
+}
@@ -4,7 +4,7 @@ use rp_pico::hal::{self, clocks::Clock, pac, usb::UsbBus, Timer, Watchdog};
 use usb_device::{class_prelude::UsbBusAllocator, prelude::*};
 use usbd_serial::SerialPort;
 
-use crate::pins::PinSet;
+use crate::{key, pins::PinSet};
 
 pub fn initialize_system() -> (UsbBusAllocator<UsbBus>, Delay, Timer, PinSet) {
 	let mut pac = pac::Peripherals::take().unwrap();
@@ -58,7 +58,12 @@ pub fn initialize_system() -> (UsbBusAllocator<UsbBus>, Delay, Timer, PinSet) {
 		pins.gpio16.into_push_pull_output().into(),
 		pins.gpio17.into_push_pull_output().into(),
 		pins.gpio18.into_push_pull_output().into(),
-		pins.gpio13.into_pull_down_input().into(),
+	);
+
+	key::start(
+		pins.gpio13.into_pull_down_input(),
+		pins.gpio12.into_pull_down_input(),
+		pins.gpio11.into_pull_down_input(),
 	);
 
 	(usb_bus, delay, timer, pin_set)
@@ -0,0 +1,135 @@
+//! Timestamps every edge of the button and paddles from their GPIO interrupt, queueing them
+//! to be taken from the main loop
+
+use core::cell::RefCell;
+
+use cortex_m::interrupt::Mutex;
+use device::hal::{KeyEvent, KeyInput};
+use embedded_hal::digital::v2::InputPin;
+use heapless::Deque;
+use rp_pico::hal::{
+	gpio::{
+		bank0::{Gpio11, Gpio12, Gpio13},
+		Interrupt, Pin, PullDownInput,
+	},
+	pac::{self, interrupt},
+};
+
+/// The pin the button is on
+pub type Button = Pin<Gpio13, PullDownInput>;
+/// The pin the dit paddle is on
+pub type DitPaddle = Pin<Gpio12, PullDownInput>;
+/// The pin the dah paddle is on
+pub type DahPaddle = Pin<Gpio11, PullDownInput>;
+
+/// The inputs whose edges are queued, indexing their queues
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Input {
+	Button,
+	Dit,
+	Dah,
+}
+
+/// Number of edges that can wait to be taken for each input, later ones are dropped
+const QUEUE_LENGTH: usize = 64;
+
+/// The inputs, handed to the interrupt
+static PINS: Mutex<RefCell<Option<(Button, DitPaddle, DahPaddle)>>> =
+	Mutex::new(RefCell::new(None));
+/// Edges waiting to be taken for each input, oldest first
+static EDGES: Mutex<RefCell<[Deque<KeyEvent, QUEUE_LENGTH>; 3]>> =
+	Mutex::new(RefCell::new([Deque::new(), Deque::new(), Deque::new()]));
+
+/// Starts timestamping the edges of the button and paddles
+///
+/// # Arguments
+/// * `button` - The button's pin
+/// * `dit` - The dit paddle's pin
+/// * `dah` - The dah paddle's pin
+pub fn start(button: Button, dit: DitPaddle, dah: DahPaddle) {
+	button.set_interrupt_enabled(Interrupt::EdgeHigh, true);
+	button.set_interrupt_enabled(Interrupt::EdgeLow, true);
+	dit.set_interrupt_enabled(Interrupt::EdgeHigh, true);
+	dit.set_interrupt_enabled(Interrupt::EdgeLow, true);
+	dah.set_interrupt_enabled(Interrupt::EdgeHigh, true);
+	dah.set_interrupt_enabled(Interrupt::EdgeLow, true);
+
+	cortex_m::interrupt::free(|cs| PINS.borrow(cs).replace(Some((button, dit, dah))));
+
+	// Safe as the interrupt only shares state through the mutexes
+	unsafe {
+		pac::NVIC::unmask(pac::Interrupt::IO_IRQ_BANK0);
+	}
+}
+
+/// Takes the oldest edge of an input that happened by a time
+///
+/// # Arguments
+/// * `input` - The input to take the edge of
+/// * `now` - Microseconds since the timer started
+pub fn take_edge(input: Input, now: u64) -> Option<KeyEvent> {
+	cortex_m::interrupt::free(|cs| {
+		let edges = &mut EDGES.borrow(cs).borrow_mut()[input as usize];
+		edges.front().filter(|edge| edge.time <= now)?;
+		edges.pop_front()
+	})
+}
+
+/// A paddle read from its queue of edges
+pub struct PaddleInput(pub Input);
+
+impl KeyInput for PaddleInput {
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent> { take_edge(self.0, now) }
+}
+
+/// Queues an edge of a pin if it has one waiting
+macro_rules! queue_edge {
+	($pin:expr, $input:expr, $time:expr, $cs:expr) => {
+		if $pin.interrupt_status(Interrupt::EdgeHigh) || $pin.interrupt_status(Interrupt::EdgeLow) {
+			// Both edges may be waiting after a fast bounce, so the level says which came last
+			let pressed = $pin.is_high().unwrap();
+			$pin.clear_interrupt(Interrupt::EdgeHigh);
+			$pin.clear_interrupt(Interrupt::EdgeLow);
+
+			EDGES.borrow($cs).borrow_mut()[$input as usize]
+				.push_back(KeyEvent {
+					time: $time,
+					pressed,
+				})
+				.unwrap_or_default();
+		}
+	};
+}
+
+/// Reads the timer's count in microseconds, as the [`SystemClock`](crate::hal::SystemClock)
+/// does through the [`Timer`](rp_pico::hal::Timer) it owns, so the times match
+fn read_counter() -> u64 {
+	// Safe as the raw counter registers are only read, which doesn't change the timer
+	let timer = unsafe { &*pac::TIMER::ptr() };
+
+	// The high word is read either side of the low one, in case the low one wrapped in between
+	loop {
+		let high = timer.timerawh.read().bits();
+		let low = timer.timerawl.read().bits();
+
+		if timer.timerawh.read().bits() == high {
+			return (u64::from(high) << 32) | u64::from(low);
+		}
+	}
+}
+
+#[interrupt]
+fn IO_IRQ_BANK0() {
+	cortex_m::interrupt::free(|cs| {
+		let mut pins = PINS.borrow(cs).borrow_mut();
+		let Some((button, dit, dah)) = pins.as_mut() else {
+			return;
+		};
+
+		let time = read_counter();
+
+		queue_edge!(button, Input::Button, time, cs);
+		queue_edge!(dit, Input::Dit, time, cs);
+		queue_edge!(dah, Input::Dah, time, cs);
+	});
+}
@@ -0,0 +1,153 @@
+#![no_std]
+#![no_main]
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+mod hal;
+mod initialization;
+mod key;
+mod pins;
+
+use device::{
+	button_mode,
+	debounce::Debounced,
+	hal::{Clock, Indicators, KeyInput, TextSink},
+	keyer_mode,
+	led::blink_codes,
+	replay_mode,
+	serial::new_line,
+	serial_mode,
+};
+use morse::{code::Code, keying::Timing, BUFFER_LENGTH};
+use rp_pico::entry;
+
+use crate::{
+	hal::{SystemClock, UsbSerial},
+	initialization::{initialize_system, initialize_usb},
+	key::{Input, PaddleInput},
+};
+
+/// Microseconds the button has to stay pressed or released for, longer than its contacts bounce
+const DEBOUNCE_WINDOW: u32 = 5_000;
+
+/// Microseconds the button is held for to select serial mode, and replay mode
+const SERIAL_MODE_HOLD: u64 = 300_000;
+const REPLAY_MODE_HOLD: u64 = 3_000_000;
+
+/// Speed the keyer sends at from the paddles
+const KEYER_TIMING: Timing = Timing::new(20.0);
+
+#[entry]
+fn main() -> ! {
+	let mut initialised = false;
+
+	let (usb_bus, delay, timer, pin_set) = initialize_system();
+	let mut pin_set = Debounced::new(pin_set, DEBOUNCE_WINDOW);
+	let mut dit = Debounced::new(PaddleInput(Input::Dit), DEBOUNCE_WINDOW);
+	let mut dah = Debounced::new(PaddleInput(Input::Dah), DEBOUNCE_WINDOW);
+	let (serial, usb_dev) = initialize_usb(&usb_bus);
+
+	let mut clock = SystemClock::new(delay, timer);
+	let mut serial = UsbSerial::new(serial, usb_dev);
+
+	loop {
+		// No clue why this has to be done, but serial wont work without it
+		if !initialised && clock.now() >= 2_000_000 {
+			initialised = true;
+			serial.write(b"Hello World!\r\n");
+		}
+
+		serial.poll();
+
+		if initialised {
+			// When the button was pressed, while it is held
+			let mut pressed_at: Option<u64> = None;
+			let mut held = 0;
+
+			serial.write(b"Press button to select button mode.");
+
+			new_line(&mut serial, &mut clock);
+
+			serial.write(b"Hold button to select serial mode.");
+
+			new_line(&mut serial, &mut clock);
+
+			serial.write(b"Hold button for 3 seconds to select replay mode.");
+
+			new_line(&mut serial, &mut clock);
+
+			serial.write(b"Press a paddle to select keyer mode.");
+
+			new_line(&mut serial, &mut clock);
+
+			loop {
+				let now = clock.now();
+
+				// Any press of a paddle selects keyer mode, and the message starts at the next one
+				let paddle_pressed = [dit.next_event(now), dah.next_event(now)]
+					.into_iter()
+					.flatten()
+					.any(|event| event.pressed);
+
+				if paddle_pressed {
+					serial.write(b"Keyer mode selected.\n\r");
+					let codes = keyer_mode(
+						&mut dit,
+						&mut dah,
+						&mut pin_set,
+						&mut clock,
+						&mut serial,
+						&KEYER_TIMING,
+					);
+					blink_forever(&mut pin_set, &mut clock, &codes);
+				}
+
+				while let Some(event) = pin_set.next_event(now) {
+					if event.pressed {
+						pressed_at = Some(event.time);
+						continue;
+					}
+
+					let Some(start) = pressed_at.take() else {
+						continue;
+					};
+
+					let codes = match event.time - start {
+						length if length > REPLAY_MODE_HOLD => {
+							replay_mode(&mut pin_set, &mut clock, &mut serial)
+						}
+						length if length > SERIAL_MODE_HOLD => serial_mode(&mut clock, &mut serial),
+						_ => {
+							serial.write(b"Button mode selected.\n\r");
+							button_mode(&mut pin_set, &mut clock, &mut serial)
+						}
+					};
+					blink_forever(&mut pin_set, &mut clock, &codes);
+				}
+
+				// Says which mode a release would select as the hold passes each length
+				let was_held = held;
+				held = pressed_at.map_or(0, |start| now.saturating_sub(start));
+
+				if was_held <= SERIAL_MODE_HOLD && held > SERIAL_MODE_HOLD {
+					serial.write(b"Serial mode selected.\n\r");
+				}
+				if was_held <= REPLAY_MODE_HOLD && held > REPLAY_MODE_HOLD {
+					serial.write(b"Replay mode selected.\n\r");
+				}
+
+				clock.delay_ms(1);
+			}
+		}
+	}
+}
+
+/// Blinks the finished message on the internal LED until the Pico is reset
+fn blink_forever<I: Indicators, C: Clock>(
+	pin_set: &mut I,
+	clock: &mut C,
+	codes: &[Code; BUFFER_LENGTH],
+) -> ! {
+	loop {
+		blink_codes(pin_set, clock, codes);
+	}
+}
@@ -0,0 +1,59 @@
+use device::hal::{Indicators, KeyEvent, KeyInput, Led};
+use embedded_hal::digital::v2::OutputPin;
+use rp2040_hal::gpio::DynPin;
+
+use crate::key::{take_edge, Input};
+
+pub struct PinSet {
+	pub internal_led:    DynPin,
+	pub letter_led:      DynPin,
+	pub word_led:        DynPin,
+	pub short_press_led: DynPin,
+	pub long_press_led:  DynPin,
+	pub passage_end_led: DynPin,
+}
+
+impl PinSet {
+	#[must_use]
+	pub const fn new(
+		internal_led: DynPin,
+		letter_led: DynPin,
+		word_led: DynPin,
+		short_press_led: DynPin,
+		long_press_led: DynPin,
+		passage_end_led: DynPin,
+	) -> Self {
+		Self {
+			internal_led,
+			letter_led,
+			word_led,
+			short_press_led,
+			long_press_led,
+			passage_end_led,
+		}
+	}
+}
+
+/// The button's edges come from its interrupt, see [`key`](crate::key)
+impl KeyInput for PinSet {
+	fn next_event(&mut self, now: u64) -> Option<KeyEvent> { take_edge(Input::Button, now) }
+}
+
+impl Indicators for PinSet {
+	fn set(&mut self, led: Led, on: bool) {
+		let pin = match led {
+			Led::Internal => &mut self.internal_led,
+			Led::Letter => &mut self.letter_led,
+			Led::Word => &mut self.word_led,
+			Led::ShortPress => &mut self.short_press_led,
+			Led::LongPress => &mut self.long_press_led,
+			Led::PassageEnd => &mut self.passage_end_led,
+		};
+
+		if on {
+			pin.set_high().unwrap();
+		} else {
+			pin.set_low().unwrap();
+		}
+	}
+}
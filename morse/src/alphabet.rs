@@ -0,0 +1,178 @@
+pub mod american;
+mod cyrillic;
+mod greek;
+mod latin;
+pub mod wabun;
+
+pub use american::American;
+pub use cyrillic::Cyrillic;
+pub use greek::Greek;
+pub use latin::{Latin, Profile};
+pub use wabun::Wabun;
+
+use crate::code::Code;
+
+/// A table of characters and the morse codes they are sent as
+pub trait Alphabet {
+	/// Converts a lowercase character to a morse code, `None` if it isn't in the table
+	fn char_to_code(&self, character: char) -> Option<Code>;
+
+	/// Converts a morse code to a lowercase character, `None` if it isn't in the table
+	fn code_to_char(&self, code: Code) -> Option<char>;
+
+	/// Converts a pair of lowercase characters sent as a single code, such as `ch`,
+	/// `None` if the pair isn't in the table
+	fn digraph_to_code(&self, _first: char, _second: char) -> Option<Code> { None }
+
+	/// Converts a morse code to a pair of lowercase characters, `None` if it isn't in the table
+	fn code_to_digraph(&self, _code: Code) -> Option<(char, char)> { None }
+}
+
+/// The alphabets that can be selected at runtime
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Table {
+	Latin(Profile),
+	Cyrillic,
+	Greek,
+	American,
+}
+
+impl Default for Table {
+	fn default() -> Self { Self::Latin(Profile::default()) }
+}
+
+impl Table {
+	pub const ALL: [Self; 9] = [
+		Self::Latin(Profile::International),
+		Self::Latin(Profile::German),
+		Self::Latin(Profile::Spanish),
+		Self::Latin(Profile::French),
+		Self::Latin(Profile::Nordic),
+		Self::Latin(Profile::Icelandic),
+		Self::Cyrillic,
+		Self::Greek,
+		Self::American,
+	];
+
+	/// The name the table is selected by
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Latin(profile) => profile.name(),
+			Self::Cyrillic => "cyrillic",
+			Self::Greek => "greek",
+			Self::American => "american",
+		}
+	}
+
+	/// Finds a table from its name, ignoring case
+	#[must_use]
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL
+			.into_iter()
+			.find(|table| table.name().eq_ignore_ascii_case(name))
+	}
+}
+
+impl Alphabet for Table {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		match self {
+			Self::Latin(profile) => profile.char_to_code(character),
+			Self::Cyrillic => Cyrillic.char_to_code(character),
+			Self::Greek => Greek.char_to_code(character),
+			Self::American => American.char_to_code(character),
+		}
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> {
+		match self {
+			Self::Latin(profile) => profile.code_to_char(code),
+			Self::Cyrillic => Cyrillic.code_to_char(code),
+			Self::Greek => Greek.code_to_char(code),
+			Self::American => American.code_to_char(code),
+		}
+	}
+
+	fn digraph_to_code(&self, first: char, second: char) -> Option<Code> {
+		match self {
+			Self::Latin(profile) => profile.digraph_to_code(first, second),
+			Self::Cyrillic | Self::Greek | Self::American => None,
+		}
+	}
+
+	fn code_to_digraph(&self, code: Code) -> Option<(char, char)> {
+		match self {
+			Self::Latin(profile) => profile.code_to_digraph(code),
+			Self::Cyrillic | Self::Greek | Self::American => None,
+		}
+	}
+}
+
+/// Builds a table of characters placed at the [`Marks::tree_index`] of their code
+///
+/// [`Marks::tree_index`]: crate::code::Marks::tree_index
+///
+/// # Panics
+/// * If a code is too long for the table or two characters share a code
+#[must_use]
+pub const fn build_decode_table<const N: usize>(codes: &[(char, Code)]) -> [Option<char>; N] {
+	let mut table = [None; N];
+	let mut index = 0;
+
+	while index < codes.len() {
+		if let (character, Code::Letter(marks)) = codes[index] {
+			let position = marks.tree_index();
+			assert!(position < N, "Code too long for decode table");
+			assert!(table[position].is_none(), "Duplicate code in table");
+			table[position] = Some(character);
+		}
+		index += 1;
+	}
+
+	table
+}
+
+/// Builds a table of codes indexed by character, starting from `first`
+///
+/// # Panics
+/// * If a character is outside of the table
+#[must_use]
+pub const fn build_encode_table<const N: usize>(
+	codes: &[(char, Code)],
+	first: char,
+) -> [Option<Code>; N] {
+	let mut table = [None; N];
+	let mut index = 0;
+
+	while index < codes.len() {
+		let (character, code) = codes[index];
+		assert!(
+			character as u32 >= first as u32 && ((character as u32 - first as u32) as usize) < N,
+			"Character outside of encode table"
+		);
+		table[(character as u32 - first as u32) as usize] = Some(code);
+		index += 1;
+	}
+
+	table
+}
+
+/// Looks up a character in a table built by [`build_encode_table`]
+#[must_use]
+pub fn encode(table: &[Option<Code>], first: char, character: char) -> Option<Code> {
+	(character as u32)
+		.checked_sub(first as u32)
+		.and_then(|index| table.get(index as usize))
+		.copied()
+		.flatten()
+}
+
+/// Looks up a code in a table built by [`build_decode_table`]
+#[must_use]
+pub fn decode(table: &[Option<char>], code: Code) -> Option<char> {
+	let Code::Letter(marks) = code else {
+		return None;
+	};
+
+	table.get(marks.tree_index()).copied().flatten()
+}
@@ -0,0 +1,100 @@
+use crate::{
+	alphabet::{build_encode_table, encode, Alphabet},
+	code::{Code, Element},
+};
+
+/// American railroad Morse, sent with spaces inside letters and dashes of three lengths
+///
+/// Shares no codes with the other alphabets, so characters become [`Code::American`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct American;
+
+const CODES: [(char, Code); 42] = [
+	(' ', Code::Space),
+	('a', Code::american(".-")),
+	('b', Code::american("-...")),
+	('c', Code::american(".. .")),
+	('d', Code::american("-..")),
+	('e', Code::american(".")),
+	('f', Code::american(".-.")),
+	('g', Code::american("--.")),
+	('h', Code::american("....")),
+	('i', Code::american("..")),
+	('j', Code::american("-.-.")),
+	('k', Code::american("-.-")),
+	('l', Code::american("L")),
+	('m', Code::american("--")),
+	('n', Code::american("-.")),
+	('o', Code::american(". .")),
+	('p', Code::american(".....")),
+	('q', Code::american("..-.")),
+	('r', Code::american(". ..")),
+	('s', Code::american("...")),
+	('t', Code::american("-")),
+	('u', Code::american("..-")),
+	('v', Code::american("...-")),
+	('w', Code::american(".--")),
+	('x', Code::american(".-..")),
+	('y', Code::american(".. ..")),
+	('z', Code::american("... .")),
+	('1', Code::american(".--.")),
+	('2', Code::american("..-..")),
+	('3', Code::american("...-.")),
+	('4', Code::american("....-")),
+	('5', Code::american("---")),
+	('6', Code::american("......")),
+	('7', Code::american("--..")),
+	('8', Code::american("-....")),
+	('9', Code::american("-..-")),
+	('0', Code::american("0")),
+	('&', Code::american(". ...")),
+	('.', Code::american("..--..")),
+	(',', Code::american(".-.-")),
+	('?', Code::american("-..-.")),
+	('!', Code::american("---.")),
+];
+
+/// Codes indexed by ASCII character
+const ENCODE_TABLE: [Option<Code>; 128] = build_encode_table(&CODES, '\0');
+
+impl Alphabet for American {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		encode(&ENCODE_TABLE, '\0', character)
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> {
+		CODES
+			.iter()
+			.find(|code_set| code_set.1 == code)
+			.map(|code_set| code_set.0)
+	}
+}
+
+/// Classifies how long the key was held down for into an element
+///
+/// # Arguments
+/// * `duration` - How long the key was held down for
+/// * `unit` - The length of a dot, in the same units as `duration`
+#[must_use]
+pub const fn classify_press(duration: u32, unit: u32) -> Element {
+	// Splits halfway between the lengths of each element
+	if duration * 2 < unit * 3 {
+		Element::Dot
+	} else if duration < unit * 3 {
+		Element::Dash
+	} else if duration * 2 < unit * 9 {
+		Element::LongDash
+	} else {
+		Element::ExtraLongDash
+	}
+}
+
+/// Whether a gap between key presses is long enough to be a space inside a letter
+///
+/// # Arguments
+/// * `duration` - How long the key was released for
+/// * `unit` - The length of a dot, in the same units as `duration`
+#[must_use]
+pub const fn is_space(duration: u32, unit: u32) -> bool {
+	duration * 2 >= unit * 3 && duration * 2 < unit * 5
+}
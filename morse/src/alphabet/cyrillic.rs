@@ -0,0 +1,62 @@
+use crate::{
+	alphabet::{build_decode_table, build_encode_table, decode, encode, Alphabet, Latin},
+	code::Code,
+};
+
+/// The Russian alphabet, falling back to [`Latin`] for digits, punctuation and Latin letters
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cyrillic;
+
+const CODES: [(char, Code); 32] = [
+	('а', Code::letter(".-")),
+	('б', Code::letter("-...")),
+	('в', Code::letter(".--")),
+	('г', Code::letter("--.")),
+	('д', Code::letter("-..")),
+	('е', Code::letter(".")),
+	('ж', Code::letter("...-")),
+	('з', Code::letter("--..")),
+	('и', Code::letter("..")),
+	('й', Code::letter(".---")),
+	('к', Code::letter("-.-")),
+	('л', Code::letter(".-..")),
+	('м', Code::letter("--")),
+	('н', Code::letter("-.")),
+	('о', Code::letter("---")),
+	('п', Code::letter(".--.")),
+	('р', Code::letter(".-.")),
+	('с', Code::letter("...")),
+	('т', Code::letter("-")),
+	('у', Code::letter("..-")),
+	('ф', Code::letter("..-.")),
+	('х', Code::letter("....")),
+	('ц', Code::letter("-.-.")),
+	('ч', Code::letter("---.")),
+	('ш', Code::letter("----")),
+	('щ', Code::letter("--.-")),
+	('ъ', Code::letter("--.--")),
+	('ы', Code::letter("-.--")),
+	('ь', Code::letter("-..-")),
+	('э', Code::letter("..-..")),
+	('ю', Code::letter("..--")),
+	('я', Code::letter(".-.-")),
+];
+
+/// Fits codes of up to 5 marks
+const DECODE_TABLE: [Option<char>; 64] = build_decode_table(&CODES);
+
+/// Codes indexed by character from `а` to `я`
+const ENCODE_TABLE: [Option<Code>; 32] = build_encode_table(&CODES, 'а');
+
+impl Alphabet for Cyrillic {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		// Ё is sent the same as Е
+		let character = if character == 'ё' { 'е' } else { character };
+
+		encode(&ENCODE_TABLE, 'а', character).or_else(|| Latin.char_to_code(character))
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> {
+		decode(&DECODE_TABLE, code).or_else(|| Latin.code_to_char(code))
+	}
+}
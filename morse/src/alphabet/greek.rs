@@ -0,0 +1,68 @@
+use crate::{
+	alphabet::{build_decode_table, build_encode_table, decode, encode, Alphabet, Latin},
+	code::Code,
+};
+
+/// The Greek alphabet, falling back to [`Latin`] for digits, punctuation and Latin letters
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Greek;
+
+const CODES: [(char, Code); 24] = [
+	('α', Code::letter(".-")),
+	('β', Code::letter("-...")),
+	('γ', Code::letter("--.")),
+	('δ', Code::letter("-..")),
+	('ε', Code::letter(".")),
+	('ζ', Code::letter("--..")),
+	('η', Code::letter("....")),
+	('θ', Code::letter("-.-.")),
+	('ι', Code::letter("..")),
+	('κ', Code::letter("-.-")),
+	('λ', Code::letter(".-..")),
+	('μ', Code::letter("--")),
+	('ν', Code::letter("-.")),
+	('ξ', Code::letter("-..-")),
+	('ο', Code::letter("---")),
+	('π', Code::letter(".--.")),
+	('ρ', Code::letter(".-.")),
+	('σ', Code::letter("...")),
+	('τ', Code::letter("-")),
+	('υ', Code::letter("-.--")),
+	('φ', Code::letter("..-.")),
+	('χ', Code::letter("----")),
+	('ψ', Code::letter("--.-")),
+	('ω', Code::letter(".--")),
+];
+
+/// Fits codes of up to 4 marks
+const DECODE_TABLE: [Option<char>; 32] = build_decode_table(&CODES);
+
+/// Codes indexed by character from `α` to `ω`
+const ENCODE_TABLE: [Option<Code>; 25] = build_encode_table(&CODES, 'α');
+
+/// Maps the final sigma and letters with accents or diaeresis to the letter they are sent as
+const fn base_letter(character: char) -> char {
+	match character {
+		'ς' => 'σ',
+		'ά' => 'α',
+		'έ' => 'ε',
+		'ή' => 'η',
+		'ί' | 'ϊ' | 'ΐ' => 'ι',
+		'ό' => 'ο',
+		'ύ' | 'ϋ' | 'ΰ' => 'υ',
+		'ώ' => 'ω',
+		_ => character,
+	}
+}
+
+impl Alphabet for Greek {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		let character = base_letter(character);
+
+		encode(&ENCODE_TABLE, 'α', character).or_else(|| Latin.char_to_code(character))
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> {
+		decode(&DECODE_TABLE, code).or_else(|| Latin.code_to_char(code))
+	}
+}
@@ -0,0 +1,215 @@
+use crate::{
+	alphabet::{build_decode_table, build_encode_table, decode, encode, Alphabet},
+	code::Code,
+};
+
+/// The international alphabet of ITU-R M.1677, used by default
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Latin;
+
+const CODES: [(char, Code); 55] = [
+	(' ', Code::Space),
+	('a', Code::letter(".-")),
+	('b', Code::letter("-...")),
+	('c', Code::letter("-.-.")),
+	('d', Code::letter("-..")),
+	('e', Code::letter(".")),
+	('f', Code::letter("..-.")),
+	('g', Code::letter("--.")),
+	('h', Code::letter("....")),
+	('i', Code::letter("..")),
+	('j', Code::letter(".---")),
+	('k', Code::letter("-.-")),
+	('l', Code::letter(".-..")),
+	('m', Code::letter("--")),
+	('n', Code::letter("-.")),
+	('o', Code::letter("---")),
+	('p', Code::letter(".--.")),
+	('q', Code::letter("--.-")),
+	('r', Code::letter(".-.")),
+	('s', Code::letter("...")),
+	('t', Code::letter("-")),
+	('u', Code::letter("..-")),
+	('v', Code::letter("...-")),
+	('w', Code::letter(".--")),
+	('x', Code::letter("-..-")),
+	('y', Code::letter("-.--")),
+	('z', Code::letter("--..")),
+	('1', Code::letter(".----")),
+	('2', Code::letter("..---")),
+	('3', Code::letter("...--")),
+	('4', Code::letter("....-")),
+	('5', Code::letter(".....")),
+	('6', Code::letter("-....")),
+	('7', Code::letter("--...")),
+	('8', Code::letter("---..")),
+	('9', Code::letter("----.")),
+	('0', Code::letter("-----")),
+	('.', Code::letter(".-.-.-")),
+	(',', Code::letter("--..--")),
+	('?', Code::letter("..--..")),
+	('\'', Code::letter(".----.")),
+	('!', Code::letter("-.-.--")),
+	('/', Code::letter("-..-.")),
+	('(', Code::letter("-.--.")),
+	(')', Code::letter("-.--.-")),
+	('&', Code::letter(".-...")),
+	(':', Code::letter("---...")),
+	(';', Code::letter("-.-.-.")),
+	('=', Code::letter("-...-")),
+	('+', Code::letter(".-.-.")),
+	('-', Code::letter("-....-")),
+	('_', Code::letter("..--.-")),
+	('"', Code::letter(".-..-.")),
+	('$', Code::letter("...-..-")),
+	('@', Code::letter(".--.-.")),
+];
+
+/// Fits codes of up to 7 marks
+const DECODE_TABLE: [Option<char>; 256] = build_decode_table(&CODES);
+
+/// Codes indexed by ASCII character
+const ENCODE_TABLE: [Option<Code>; 128] = build_encode_table(&CODES, '\0');
+
+impl Latin {
+	/// Converts a lowercase character to a morse code in a const context,
+	/// such as for the [`morse!`](crate::morse) macro
+	#[must_use]
+	pub const fn const_char_to_code(character: char) -> Option<Code> {
+		let index = character as usize;
+
+		if index < ENCODE_TABLE.len() {
+			ENCODE_TABLE[index]
+		} else {
+			None
+		}
+	}
+}
+
+impl Alphabet for Latin {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		encode(&ENCODE_TABLE, '\0', character)
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> { decode(&DECODE_TABLE, code) }
+}
+
+/// Sets of accented and extra letters added to [`Latin`] for a language
+///
+/// Letters sharing a code, such as `ä` and `æ`, decode as the first one listed for the profile.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Profile {
+	/// No extra letters
+	#[default]
+	International,
+	German,
+	Spanish,
+	French,
+	Nordic,
+	Icelandic,
+}
+
+const GERMAN: [(char, Code); 3] = [
+	('ä', Code::letter(".-.-")),
+	('ö', Code::letter("---.")),
+	('ü', Code::letter("..--")),
+];
+
+const SPANISH: [(char, Code); 4] = [
+	('á', Code::letter(".--.-")),
+	('é', Code::letter("..-..")),
+	('ñ', Code::letter("--.--")),
+	('ü', Code::letter("..--")),
+];
+
+const FRENCH: [(char, Code); 4] = [
+	('à', Code::letter(".--.-")),
+	('ç', Code::letter("-.-..")),
+	('è', Code::letter(".-..-")),
+	('é', Code::letter("..-..")),
+];
+
+const NORDIC: [(char, Code); 5] = [
+	('å', Code::letter(".--.-")),
+	('ä', Code::letter(".-.-")),
+	('ö', Code::letter("---.")),
+	('æ', Code::letter(".-.-")),
+	('ø', Code::letter("---.")),
+];
+
+const ICELANDIC: [(char, Code); 6] = [
+	('á', Code::letter(".--.-")),
+	('æ', Code::letter(".-.-")),
+	('ð', Code::letter("..--.")),
+	('é', Code::letter("..-..")),
+	('ö', Code::letter("---.")),
+	('þ', Code::letter(".--..")),
+];
+
+/// `ch` is sent as a single letter in German and Spanish
+const CH: Code = Code::letter("----");
+
+impl Profile {
+	pub const ALL: [Self; 6] = [
+		Self::International,
+		Self::German,
+		Self::Spanish,
+		Self::French,
+		Self::Nordic,
+		Self::Icelandic,
+	];
+
+	/// The name the profile is selected by
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::International => "latin",
+			Self::German => "german",
+			Self::Spanish => "spanish",
+			Self::French => "french",
+			Self::Nordic => "nordic",
+			Self::Icelandic => "icelandic",
+		}
+	}
+
+	/// The extra letters the profile adds
+	#[must_use]
+	pub const fn extensions(self) -> &'static [(char, Code)] {
+		match self {
+			Self::International => &[],
+			Self::German => &GERMAN,
+			Self::Spanish => &SPANISH,
+			Self::French => &FRENCH,
+			Self::Nordic => &NORDIC,
+			Self::Icelandic => &ICELANDIC,
+		}
+	}
+
+	const fn has_ch(self) -> bool { matches!(self, Self::German | Self::Spanish) }
+}
+
+impl Alphabet for Profile {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		self.extensions()
+			.iter()
+			.find(|extension| extension.0 == character)
+			.map(|extension| extension.1)
+			.or_else(|| Latin.char_to_code(character))
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> {
+		self.extensions()
+			.iter()
+			.find(|extension| extension.1 == code)
+			.map(|extension| extension.0)
+			.or_else(|| Latin.code_to_char(code))
+	}
+
+	fn digraph_to_code(&self, first: char, second: char) -> Option<Code> {
+		(self.has_ch() && (first, second) == ('c', 'h')).then_some(CH)
+	}
+
+	fn code_to_digraph(&self, code: Code) -> Option<(char, char)> {
+		(self.has_ch() && code == CH).then_some(('c', 'h'))
+	}
+}
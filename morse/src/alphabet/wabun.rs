@@ -0,0 +1,129 @@
+use crate::{
+	alphabet::{build_decode_table, build_encode_table, decode, encode, Alphabet, Latin},
+	code::Code,
+};
+
+/// Japanese Wabun code, shifted into with `<DO>` and back out of with `<SN>`
+///
+/// Only full size katakana without marks are in the table, see [`decompose`] for the rest.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Wabun;
+
+const CODES: [(char, Code); 53] = [
+	('ア', Code::letter("--.--")),
+	('イ', Code::letter(".-")),
+	('ウ', Code::letter("..-")),
+	('エ', Code::letter("-.---")),
+	('オ', Code::letter(".-...")),
+	('カ', Code::letter(".-..")),
+	('キ', Code::letter("-.-..")),
+	('ク', Code::letter("...-")),
+	('ケ', Code::letter("-.--")),
+	('コ', Code::letter("----")),
+	('サ', Code::letter("-.-.-")),
+	('シ', Code::letter("--.-.")),
+	('ス', Code::letter("---.-")),
+	('セ', Code::letter(".---.")),
+	('ソ', Code::letter("---.")),
+	('タ', Code::letter("-.")),
+	('チ', Code::letter("..-.")),
+	('ツ', Code::letter(".--.")),
+	('テ', Code::letter(".-.--")),
+	('ト', Code::letter("..-..")),
+	('ナ', Code::letter(".-.")),
+	('ニ', Code::letter("-.-.")),
+	('ヌ', Code::letter("....")),
+	('ネ', Code::letter("--.-")),
+	('ノ', Code::letter("..--")),
+	('ハ', Code::letter("-...")),
+	('ヒ', Code::letter("--..-")),
+	('フ', Code::letter("--..")),
+	('ヘ', Code::letter(".")),
+	('ホ', Code::letter("-..")),
+	('マ', Code::letter("-..-")),
+	('ミ', Code::letter("..-.-")),
+	('ム', Code::letter("-")),
+	('メ', Code::letter("-...-")),
+	('モ', Code::letter("-..-.")),
+	('ヤ', Code::letter(".--")),
+	('ユ', Code::letter("-..--")),
+	('ヨ', Code::letter("--")),
+	('ラ', Code::letter("...")),
+	('リ', Code::letter("--.")),
+	('ル', Code::letter("-.--.")),
+	('レ', Code::letter("---")),
+	('ロ', Code::letter(".-.-")),
+	('ワ', Code::letter("-.-")),
+	('ヰ', Code::letter(".-..-")),
+	('ヱ', Code::letter(".--..")),
+	('ヲ', Code::letter(".---")),
+	('ン', Code::letter(".-.-.")),
+	('ー', Code::letter(".--.-")),
+	('゛', Code::letter("..")),
+	('゜', Code::letter("..--.")),
+	('、', Code::letter(".-.-.-")),
+	('。', Code::letter(".-.-..")),
+];
+
+/// Fits codes of up to 6 marks
+const DECODE_TABLE: [Option<char>; 128] = build_decode_table(&CODES);
+
+/// Codes indexed by character from `、` to `ー`
+const ENCODE_TABLE: [Option<Code>; 252] = build_encode_table(&CODES, '、');
+
+impl Alphabet for Wabun {
+	fn char_to_code(&self, character: char) -> Option<Code> {
+		encode(&ENCODE_TABLE, '、', character)
+	}
+
+	fn code_to_char(&self, code: Code) -> Option<char> {
+		decode(&DECODE_TABLE, code).or_else(|| Latin.code_to_char(code))
+	}
+}
+
+/// Splits a kana into the full size katakana and the voicing mark it is sent as
+///
+/// Hiragana are treated as their katakana, small kana as their full size kana,
+/// and voiced or semi-voiced kana such as `ガ` or `パ` are followed by `゛` or `゜`.
+#[must_use]
+pub fn decompose(character: char) -> (char, Option<char>) {
+	let mut code = u32::from(character);
+
+	// Hiragana sit 0x60 before their katakana
+	if (0x3041..=0x3096).contains(&code) {
+		code += 0x60;
+	}
+
+	let (code, mark) = match code {
+		// Small ァ ィ ゥ ェ ォ sit before their full size kana
+		0x30A1..=0x30A9 if code % 2 == 1 => (code + 1, None),
+		// ガ to ヂ follow their unvoiced kana
+		0x30AB..=0x30C2 if code % 2 == 0 => (code - 1, Some('゛')),
+		// Small ッ, ャ, ュ, ョ and ヮ
+		0x30C3 | 0x30E3 | 0x30E5 | 0x30E7 | 0x30EE => (code + 1, None),
+		// ヅ デ ド
+		0x30C4..=0x30C9 if code % 2 == 1 => (code - 1, Some('゛')),
+		// The ハ row has both voiced and semi-voiced kana
+		0x30CF..=0x30DD => match (code - 0x30CF) % 3 {
+			1 => (code - 1, Some('゛')),
+			2 => (code - 2, Some('゜')),
+			_ => (code, None),
+		},
+		// ヴ
+		0x30F4 => (0x30A6, Some('゛')),
+		_ => (code, None),
+	};
+
+	(char::from_u32(code).unwrap_or(character), mark)
+}
+
+/// Joins a katakana and a following voicing mark into a single kana, the reverse of [`decompose`]
+#[must_use]
+pub fn compose(kana: char, mark: char) -> Option<char> {
+	let code = u32::from(kana);
+
+	[code + 1, code + 2, 0x30F4]
+		.into_iter()
+		.filter_map(char::from_u32)
+		.find(|candidate| decompose(*candidate) == (kana, Some(mark)))
+}
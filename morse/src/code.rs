@@ -0,0 +1,436 @@
+use heapless::String;
+
+use crate::alphabet::Alphabet;
+
+/// Maximum number of marks a single code can hold
+pub const MAX_MARKS: usize = 16;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mark {
+	Dot,
+	Dash,
+}
+
+/// A variable length sequence of marks packed into an integer
+///
+/// Each mark takes a single bit, `0` for a dot and `1` for a dash,
+/// with the first mark in the most significant used bit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Marks {
+	bits: u16,
+	len:  u8,
+}
+
+impl Marks {
+	/// Creates an empty mark sequence
+	#[must_use]
+	pub const fn new() -> Self { Self { bits: 0, len: 0 } }
+
+	/// Creates a mark sequence from a pattern of `.` and `-` characters
+	///
+	/// # Panics
+	/// * If the pattern contains any other character or is longer than [`MAX_MARKS`]
+	#[must_use]
+	pub const fn from_pattern(pattern: &str) -> Self {
+		let pattern = pattern.as_bytes();
+		let mut marks = Self::new();
+		let mut index = 0;
+
+		assert!(pattern.len() <= MAX_MARKS, "Too many marks in pattern");
+
+		while index < pattern.len() {
+			let bit = match pattern[index] {
+				b'.' => 0,
+				b'-' => 1,
+				_ => panic!("Invalid mark in pattern"),
+			};
+			marks.bits = (marks.bits << 1) | bit;
+			marks.len += 1;
+			index += 1;
+		}
+
+		marks
+	}
+
+	/// Adds a mark to the end of the sequence, returning it back if the sequence is full
+	///
+	/// # Errors
+	/// * If the sequence already holds [`MAX_MARKS`] marks
+	pub fn push(&mut self, mark: Mark) -> Result<(), Mark> {
+		if self.is_full() {
+			return Err(mark);
+		}
+		self.bits = (self.bits << 1) | u16::from(mark == Mark::Dash);
+		self.len += 1;
+		Ok(())
+	}
+
+	/// Gets the mark at the given position
+	#[must_use]
+	pub const fn get(self, index: usize) -> Option<Mark> {
+		if index >= self.len() {
+			return None;
+		}
+		if (self.bits >> (self.len() - 1 - index)) & 1 == 1 {
+			Some(Mark::Dash)
+		} else {
+			Some(Mark::Dot)
+		}
+	}
+
+	/// Position of the sequence in a dot-left, dash-right binary tree stored as an array
+	///
+	/// The root is at `1` and each mark moves from `n` to `2n` for a dot or `2n + 1` for a dash.
+	#[must_use]
+	pub const fn tree_index(self) -> usize { (1 << self.len) | self.bits as usize }
+
+	#[must_use]
+	pub const fn len(self) -> usize { self.len as usize }
+
+	#[must_use]
+	pub const fn is_empty(self) -> bool { self.len == 0 }
+
+	#[must_use]
+	pub const fn is_full(self) -> bool { self.len() == MAX_MARKS }
+
+	/// Iterates over the marks from first to last
+	pub fn iter(self) -> impl Iterator<Item = Mark> {
+		(0..self.len()).filter_map(move |index| self.get(index))
+	}
+}
+
+/// Maximum number of elements a single American Morse code can hold
+pub const MAX_ELEMENTS: usize = 10;
+/// Length of the string returned by [`Code::to_marks`], an element symbol takes up to 3 bytes
+pub const MARKS_LENGTH: usize = MAX_ELEMENTS * 3 + 1;
+
+/// The timed parts of an American Morse character
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Element {
+	/// Key down for one unit
+	Dot,
+	/// Key down for two units
+	Dash,
+	/// Key down for four units, the letter `L`
+	LongDash,
+	/// Key down for five units, the digit `0`
+	ExtraLongDash,
+	/// Key up for one unit more than the gap between elements, such as in `C`
+	Space,
+}
+
+impl Element {
+	/// How many time units the key is held down for
+	#[must_use]
+	pub const fn units(self) -> u32 {
+		match self {
+			Self::Dot => 1,
+			Self::Dash => 2,
+			Self::LongDash => 4,
+			Self::ExtraLongDash => 5,
+			Self::Space => 0,
+		}
+	}
+
+	/// The character the element is shown as
+	#[must_use]
+	pub const fn symbol(self) -> char {
+		match self {
+			Self::Dot => '.',
+			Self::Dash => '-',
+			Self::LongDash => '—',
+			Self::ExtraLongDash => '⸻',
+			Self::Space => ' ',
+		}
+	}
+
+	const fn bits(self) -> u32 {
+		match self {
+			Self::Dot => 0,
+			Self::Dash => 1,
+			Self::LongDash => 2,
+			Self::ExtraLongDash => 3,
+			Self::Space => 4,
+		}
+	}
+
+	const fn from_bits(bits: u32) -> Self {
+		match bits {
+			0 => Self::Dot,
+			1 => Self::Dash,
+			2 => Self::LongDash,
+			3 => Self::ExtraLongDash,
+			_ => Self::Space,
+		}
+	}
+}
+
+/// A variable length sequence of American Morse elements packed into an integer
+///
+/// Each element takes three bits, with the first element in the most significant used bits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Elements {
+	bits: u32,
+	len:  u8,
+}
+
+impl Elements {
+	/// Creates an empty element sequence
+	#[must_use]
+	pub const fn new() -> Self { Self { bits: 0, len: 0 } }
+
+	/// Creates an element sequence from a pattern of `.`, `-`, `L` for a long dash,
+	/// `0` for an extra long dash and ` ` for a space
+	///
+	/// # Panics
+	/// * If the pattern contains any other character or is longer than [`MAX_ELEMENTS`]
+	#[must_use]
+	pub const fn from_pattern(pattern: &str) -> Self {
+		let pattern = pattern.as_bytes();
+		let mut elements = Self::new();
+		let mut index = 0;
+
+		assert!(
+			pattern.len() <= MAX_ELEMENTS,
+			"Too many elements in pattern"
+		);
+
+		while index < pattern.len() {
+			let element = match pattern[index] {
+				b'.' => Element::Dot,
+				b'-' => Element::Dash,
+				b'L' => Element::LongDash,
+				b'0' => Element::ExtraLongDash,
+				b' ' => Element::Space,
+				_ => panic!("Invalid element in pattern"),
+			};
+			elements.bits = (elements.bits << 3) | element.bits();
+			elements.len += 1;
+			index += 1;
+		}
+
+		elements
+	}
+
+	/// Adds an element to the end of the sequence, returning it back if the sequence is full
+	///
+	/// # Errors
+	/// * If the sequence already holds [`MAX_ELEMENTS`] elements
+	pub const fn push(&mut self, element: Element) -> Result<(), Element> {
+		if self.is_full() {
+			return Err(element);
+		}
+		self.bits = (self.bits << 3) | element.bits();
+		self.len += 1;
+		Ok(())
+	}
+
+	/// Gets the element at the given position
+	#[must_use]
+	pub const fn get(self, index: usize) -> Option<Element> {
+		if index >= self.len() {
+			return None;
+		}
+		Some(Element::from_bits(
+			(self.bits >> (3 * (self.len() - 1 - index))) & 0b111,
+		))
+	}
+
+	#[must_use]
+	pub const fn len(self) -> usize { self.len as usize }
+
+	#[must_use]
+	pub const fn is_empty(self) -> bool { self.len == 0 }
+
+	#[must_use]
+	pub const fn is_full(self) -> bool { self.len() == MAX_ELEMENTS }
+
+	/// Iterates over the elements from first to last
+	pub fn iter(self) -> impl Iterator<Item = Element> {
+		(0..self.len()).filter_map(move |index| self.get(index))
+	}
+}
+
+/// Procedural signals sent as a single run-together character
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Prosign {
+	/// End of message
+	Ar,
+	/// Wait
+	As,
+	/// Break, new paragraph
+	Bt,
+	/// Start of transmission
+	Ct,
+	/// Shift into Wabun code
+	Do,
+	/// Go ahead, named station only
+	Kn,
+	/// End of contact
+	Sk,
+	/// Understood, also shifts out of Wabun code
+	Sn,
+	/// Distress
+	Sos,
+	/// Correction, eight dots
+	Hh,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Code {
+	Letter(Marks),
+	Prosign(Prosign),
+	/// A character of American Morse, which has spaces and dashes the marks of a letter can't hold
+	American(Elements),
+	Space,
+	None,
+}
+
+const PROSIGNS: [(Prosign, &str, Marks); 10] = [
+	(Prosign::Ar, "AR", Marks::from_pattern(".-.-.")),
+	(Prosign::As, "AS", Marks::from_pattern(".-...")),
+	(Prosign::Bt, "BT", Marks::from_pattern("-...-")),
+	(Prosign::Ct, "CT", Marks::from_pattern("-.-.-")),
+	(Prosign::Do, "DO", Marks::from_pattern("-..---")),
+	(Prosign::Kn, "KN", Marks::from_pattern("-.--.")),
+	(Prosign::Sk, "SK", Marks::from_pattern("...-.-")),
+	(Prosign::Sn, "SN", Marks::from_pattern("...-.")),
+	(Prosign::Sos, "SOS", Marks::from_pattern("...---...")),
+	(Prosign::Hh, "HH", Marks::from_pattern("........")),
+];
+
+impl Prosign {
+	/// The letters the prosign is written as, such as `SK`
+	#[must_use]
+	pub fn name(self) -> &'static str {
+		PROSIGNS
+			.iter()
+			.find(|prosign| prosign.0 == self)
+			.map_or("", |prosign| prosign.1)
+	}
+
+	/// The marks the prosign is sent as
+	#[must_use]
+	pub fn marks(self) -> Marks {
+		PROSIGNS
+			.iter()
+			.find(|prosign| prosign.0 == self)
+			.map_or(Marks::new(), |prosign| prosign.2)
+	}
+
+	/// Finds a prosign from its written letters, ignoring case
+	#[must_use]
+	pub const fn from_name(name: &str) -> Option<Self> {
+		let mut index = 0;
+
+		while index < PROSIGNS.len() {
+			if PROSIGNS[index].1.eq_ignore_ascii_case(name) {
+				return Some(PROSIGNS[index].0);
+			}
+			index += 1;
+		}
+
+		None
+	}
+
+	/// Finds a prosign sent as the given marks
+	#[must_use]
+	pub fn from_marks(marks: Marks) -> Option<Self> {
+		PROSIGNS
+			.iter()
+			.find(|prosign| prosign.2 == marks)
+			.map(|prosign| prosign.0)
+	}
+}
+
+impl Code {
+	/// Creates a letter code from a pattern of `.` and `-` characters
+	#[must_use]
+	pub const fn letter(pattern: &str) -> Self { Self::Letter(Marks::from_pattern(pattern)) }
+
+	/// Creates an American Morse code from a pattern, see [`Elements::from_pattern`]
+	#[must_use]
+	pub const fn american(pattern: &str) -> Self { Self::American(Elements::from_pattern(pattern)) }
+
+	/// Creates a code from keyed marks
+	///
+	/// Characters in the alphabet take priority, so marks shared with a prosign such as `+`
+	/// and `<AR>` become a letter, other known prosigns become a [`Code::Prosign`].
+	/// [`read_standalone_prosigns`](crate::read_standalone_prosigns) reads the shared marks
+	/// as the prosign once it is known they were sent on their own.
+	#[must_use]
+	pub fn from_marks<A: Alphabet + ?Sized>(marks: Marks, alphabet: &A) -> Self {
+		let code = Self::Letter(marks);
+
+		if alphabet.code_to_char(code).is_none() && alphabet.code_to_digraph(code).is_none() {
+			if let Some(prosign) = Prosign::from_marks(marks) {
+				return Self::Prosign(prosign);
+			}
+		}
+		code
+	}
+
+	/// Gets the marks the code is sent as
+	#[must_use]
+	pub fn marks(self) -> Option<Marks> {
+		match self {
+			Self::Letter(marks) => Some(marks),
+			Self::Prosign(prosign) => Some(prosign.marks()),
+			Self::American(_) | Self::Space | Self::None => None,
+		}
+	}
+
+	/// Converts a morse code to a character using the given alphabet,
+	/// `None` if it isn't in the alphabet
+	pub fn to_char<A: Alphabet + ?Sized>(self, alphabet: &A) -> Option<char> {
+		if self == Self::Space {
+			return Some(' ');
+		}
+
+		alphabet.code_to_char(self)
+	}
+
+	/// Converts a character to a morse code using the given alphabet,
+	/// `None` if it isn't in the alphabet
+	pub fn char_to_code<A: Alphabet + ?Sized>(character: char, alphabet: &A) -> Option<Self> {
+		alphabet.char_to_code(character)
+	}
+
+	/// Converts a morse code array to a string of marks
+	///
+	/// # Panics
+	/// Never, the string has room for the longest American Morse code
+	#[must_use]
+	pub fn to_marks(self) -> String<MARKS_LENGTH> {
+		let mut string = String::<MARKS_LENGTH>::new();
+
+		match self {
+			Self::Letter(_) | Self::Prosign(_) => {
+				for mark in self.marks().unwrap_or_default().iter() {
+					match mark {
+						Mark::Dot => {
+							string.push('.').unwrap();
+						}
+						Mark::Dash => {
+							string.push('-').unwrap();
+						}
+					}
+				}
+				string.push(' ').unwrap();
+			}
+			Self::American(elements) => {
+				for element in elements.iter() {
+					string.push(element.symbol()).unwrap();
+				}
+				string.push(' ').unwrap();
+			}
+			Self::Space => {
+				string.push(' ').unwrap();
+				string.push(' ').unwrap();
+			}
+			Self::None => {}
+		}
+
+		String::from(string.trim())
+	}
+}
@@ -0,0 +1,71 @@
+use crate::{
+	alphabet::{Alphabet, Wabun},
+	code::{Code, Prosign},
+};
+
+/// What a single code decodes to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Decoded {
+	Char(char),
+	/// A pair of characters sent as a single code, such as `ch`
+	Digraph(char, char),
+	Prosign(Prosign),
+	Unknown,
+	None,
+}
+
+/// Decodes codes one at a time, shifting into Wabun code on `<DO>` and back to the alphabet on `<SN>`
+pub struct Decoder<'a, A: Alphabet + ?Sized> {
+	alphabet: &'a A,
+	wabun:    bool,
+}
+
+impl<'a, A: Alphabet + ?Sized> Decoder<'a, A> {
+	#[must_use]
+	pub const fn new(alphabet: &'a A) -> Self {
+		Self {
+			alphabet,
+			wabun: false,
+		}
+	}
+
+	/// Whether codes are currently decoded as Wabun code
+	#[must_use]
+	pub const fn is_wabun(&self) -> bool { self.wabun }
+
+	/// Decodes the next code, updating the shift state
+	pub fn decode(&mut self, code: Code) -> Decoded {
+		match code {
+			Code::Prosign(Prosign::Do) => {
+				self.wabun = true;
+				Decoded::None
+			}
+			Code::Prosign(Prosign::Sn) if self.wabun => {
+				self.wabun = false;
+				Decoded::None
+			}
+			// Kana can share marks with a prosign, so prosigns are looked up by their marks too
+			Code::Letter(_) | Code::Prosign(_) if self.wabun => {
+				let marks = code.marks().unwrap_or_default();
+
+				match (Wabun.code_to_char(Code::Letter(marks)), code) {
+					(Some(char), _) => Decoded::Char(char),
+					(None, Code::Prosign(prosign)) => Decoded::Prosign(prosign),
+					(None, _) => Decoded::Unknown,
+				}
+			}
+			Code::Letter(_) | Code::American(_) => {
+				if let Some(char) = self.alphabet.code_to_char(code) {
+					Decoded::Char(char)
+				} else if let Some((first, second)) = self.alphabet.code_to_digraph(code) {
+					Decoded::Digraph(first, second)
+				} else {
+					Decoded::Unknown
+				}
+			}
+			Code::Prosign(prosign) => Decoded::Prosign(prosign),
+			Code::Space => Decoded::Char(' '),
+			Code::None => Decoded::None,
+		}
+	}
+}
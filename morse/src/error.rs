@@ -0,0 +1,44 @@
+use core::fmt::{self, Display, Formatter};
+
+use crate::code::{Code, MAX_MARKS};
+
+/// Problems converting between text and morse code
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+	/// A character that isn't in the alphabet, at a zero-based character position
+	UnknownCharacter { character: char, position: usize },
+	/// A prosign name in angle brackets that isn't known, at the position of its `<`
+	UnknownProsign { position: usize },
+	/// A code that isn't in the alphabet, at a zero-based code position
+	UnknownCode { code: Code, position: usize },
+	/// The message doesn't fit in the buffer
+	BufferOverflow,
+	/// A letter was keyed with more marks than a code can hold
+	TooManyMarks,
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::UnknownCharacter {
+				character,
+				position,
+			} => write!(f, "Unknown character '{character}' at position {position}"),
+			Self::UnknownProsign { position } => {
+				write!(f, "Unknown prosign at position {position}")
+			}
+			Self::UnknownCode { code, position } => {
+				write!(
+					f,
+					"Unknown code '{}' at position {position}",
+					code.to_marks()
+				)
+			}
+			Self::BufferOverflow => write!(f, "Message is too long for the buffer"),
+			Self::TooManyMarks => write!(f, "Letter has more than {MAX_MARKS} marks"),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,209 @@
+//! Times codes as a stream of on and off [`Span`]s for keying an LED, buzzer, transmitter or tone
+//!
+//! A dot is on for one unit and a dash for three, with a unit off between them.
+//! Letters are three units apart and words seven, and with Farnsworth spacing those gaps
+//! are stretched to make up a slower overall speed. American Morse elements are held for their
+//! [`units`](Element::units), with a space inside a letter one unit longer than between elements.
+
+use heapless::Vec;
+
+use crate::{
+	code::{Code, Element, Mark, MAX_ELEMENTS, MAX_MARKS},
+	timing::Span,
+};
+
+/// Most spans a single code is keyed as, each mark is followed by a gap and then the letter by one
+const CODE_SPANS: usize = if MAX_MARKS > MAX_ELEMENTS {
+	MAX_MARKS * 2 + 1
+} else {
+	MAX_ELEMENTS * 2 + 1
+};
+
+/// How fast codes are keyed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Timing {
+	/// Speed of the letters in words per minute, a unit is `1.2 / wpm` seconds
+	pub wpm:            f32,
+	/// Overall speed in words per minute, slower than `wpm` to stretch the gaps
+	/// between letters and words with Farnsworth spacing
+	pub farnsworth_wpm: f32,
+	/// Percentage of a dot and the gap after it that the dot is on for, 50 being standard.
+	/// Heavier weights lengthen every mark and shorten the gap after it by the same time.
+	pub weight:         f32,
+}
+
+impl Default for Timing {
+	/// The speed the LED blinks at
+	fn default() -> Self { Self::new(6.0) }
+}
+
+impl Timing {
+	/// Standard timing at a speed, without Farnsworth spacing
+	#[must_use]
+	pub const fn new(wpm: f32) -> Self {
+		Self {
+			wpm,
+			farnsworth_wpm: wpm,
+			weight: 50.0,
+		}
+	}
+
+	/// Length of a unit between and inside letters in microseconds
+	#[must_use]
+	pub fn unit(&self) -> u32 { to_micros(1.2 / self.wpm) }
+
+	/// Length of a unit of the gaps between letters and words in microseconds
+	///
+	/// With Farnsworth spacing the letters keep their speed, and the 19 units of gaps in the
+	/// standard word `PARIS` are stretched to make up the slower overall speed.
+	#[must_use]
+	pub fn gap_unit(&self) -> u32 {
+		if self.farnsworth_wpm >= self.wpm {
+			return self.unit();
+		}
+
+		to_micros(
+			libm::fmaf(60.0, self.wpm, -37.2 * self.farnsworth_wpm)
+				/ (19.0 * self.farnsworth_wpm * self.wpm),
+		)
+	}
+
+	/// How much longer each mark is than standard, and each gap after a mark shorter,
+	/// in microseconds
+	fn extra(&self) -> i64 {
+		let weight = self.weight.clamp(0.0, 100.0);
+		#[allow(clippy::cast_possible_truncation)]
+		let extra = (1.2 / self.wpm * (weight - 50.0) / 50.0 * 1_000_000.0) as i64;
+		extra
+	}
+}
+
+/// Keys codes as spans, which alternate between on and off
+///
+/// # Arguments
+/// * `codes` - The codes to key, such as from [`string_to_codes`](crate::string_to_codes)
+/// * `timing` - How fast to key them
+#[must_use]
+pub fn keying<I: IntoIterator<Item = Code>>(codes: I, timing: &Timing) -> Keying<I::IntoIter> {
+	Keying {
+		codes:    codes.into_iter(),
+		unit:     i64::from(timing.unit()),
+		gap_unit: i64::from(timing.gap_unit()),
+		extra:    timing.extra(),
+		spans:    Vec::new(),
+		next:     0,
+		pending:  None,
+	}
+}
+
+/// Iterator over the spans codes are keyed as, see [`keying`]
+#[derive(Clone, Debug)]
+pub struct Keying<I: Iterator<Item = Code>> {
+	codes:    I,
+	/// Lengths in microseconds
+	unit:     i64,
+	gap_unit: i64,
+	extra:    i64,
+	/// Spans of the current code, and the index of the next one
+	spans:    Vec<Span, CODE_SPANS>,
+	next:     usize,
+	/// Span being built up from the end of one code and the start of the next
+	pending:  Option<Span>,
+}
+
+impl<I: Iterator<Item = Code>> Iterator for Keying<I> {
+	type Item = Span;
+
+	fn next(&mut self) -> Option<Span> {
+		loop {
+			let Some(span) = self.next_span() else {
+				return self.pending.take();
+			};
+
+			match &mut self.pending {
+				// Gaps after a letter and for a space run together
+				Some(pending) if pending.on == span.on => {
+					pending.duration = pending.duration.saturating_add(span.duration);
+				}
+				pending => {
+					if let Some(finished) = pending.replace(span) {
+						return Some(finished);
+					}
+				}
+			}
+		}
+	}
+}
+
+impl<I: Iterator<Item = Code>> Keying<I> {
+	/// The next span of a code, moving on to the next code when one is finished
+	fn next_span(&mut self) -> Option<Span> {
+		while self.next >= self.spans.len() {
+			let code = self.codes.next()?;
+			self.spans = self.code_spans(code);
+			self.next = 0;
+		}
+
+		self.next += 1;
+		Some(self.spans[self.next - 1])
+	}
+
+	fn code_spans(&self, code: Code) -> Vec<Span, CODE_SPANS> {
+		let mut spans = Vec::new();
+
+		match code {
+			// Prosigns are sent as a single letter without the inter-letter gap
+			Code::Letter(_) | Code::Prosign(_) => {
+				for mark in code.marks().unwrap_or_default().iter() {
+					let units = match mark {
+						Mark::Dot => 1,
+						Mark::Dash => 3,
+					};
+					self.push_mark(&mut spans, units);
+				}
+			}
+			Code::American(elements) => {
+				for element in elements.iter() {
+					if element == Element::Space {
+						push(&mut spans, false, self.unit);
+					} else {
+						self.push_mark(&mut spans, i64::from(element.units()));
+					}
+				}
+			}
+			Code::Space => {}
+			Code::None => return spans,
+		}
+
+		// A letter ends with a gap of three gap units, of which a unit follows its last mark,
+		// and a space makes it up to seven
+		let gap = if code == Code::Space {
+			self.gap_unit * 4
+		} else {
+			self.gap_unit * 3 - self.unit
+		};
+		push(&mut spans, false, gap);
+
+		spans
+	}
+
+	/// Adds a mark and the gap after it
+	fn push_mark(&self, spans: &mut Vec<Span, CODE_SPANS>, units: i64) {
+		push(spans, true, self.unit * units + self.extra);
+		push(spans, false, self.unit - self.extra);
+	}
+}
+
+/// Adds a span, leaving out those with no length
+fn push(spans: &mut Vec<Span, CODE_SPANS>, on: bool, duration: i64) {
+	if let Ok(duration @ 1..) = u32::try_from(duration) {
+		spans.push(Span { on, duration }).unwrap_or_default();
+	}
+}
+
+/// Converts seconds to microseconds
+fn to_micros(seconds: f32) -> u32 {
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	let micros = (seconds * 1_000_000.0) as u32;
+	micros
+}
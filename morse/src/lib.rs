@@ -0,0 +1,324 @@
+//! Morse code tables, encoding and decoding shared by the firmware and host tools
+//!
+//! The crate is `no_std` unless the `std` feature is enabled.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+pub mod alphabet;
+pub mod code;
+pub mod decoder;
+pub mod error;
+pub mod keying;
+pub mod literal;
+pub mod timing;
+pub mod tone;
+#[cfg(feature = "std")]
+pub mod wav;
+
+use core::{iter::Enumerate, str::Chars};
+
+use heapless::{String, Vec};
+
+use crate::{
+	alphabet::{wabun, Alphabet, Wabun},
+	code::{Code, Prosign},
+	decoder::{Decoded, Decoder},
+	error::Error,
+};
+
+/// Number of codes in a message buffer
+pub const BUFFER_LENGTH: usize = 64;
+/// Leaves room for every code in a buffer to be decoded as a multi-byte character
+/// or a prosign such as `<SOS>`
+pub const STRING_LENGTH: usize = BUFFER_LENGTH * 5;
+
+/// Converts a morse code array to a string using the given alphabet
+///
+/// Wabun code is followed from a `<DO>` until the next `<SN>`.
+///
+/// # Errors
+/// * [`Error::UnknownCode`] - If a code isn't in the alphabet
+/// * [`Error::BufferOverflow`] - If the string doesn't fit in [`STRING_LENGTH`]
+pub fn codes_to_string<A: Alphabet + ?Sized>(
+	codes: &[Code; BUFFER_LENGTH],
+	alphabet: &A,
+) -> Result<String<STRING_LENGTH>, Error> {
+	let mut string = String::new();
+
+	decode_each(codes.iter().copied(), alphabet, |char| {
+		string.push(char).map_err(|()| Error::BufferOverflow)
+	})?;
+
+	Ok(string)
+}
+
+/// Converts a string to a morse code array using the given alphabet
+///
+/// Prosigns are written in angle brackets, such as `<SK>`.
+/// Pairs of characters sent as a single code, such as `ch`, are looked up before single characters.
+/// Kana are sent as Wabun code, with `<DO>` and `<SN>` added around them.
+///
+/// # Errors
+/// * [`Error::UnknownCharacter`] - If a character isn't in the alphabet
+/// * [`Error::UnknownProsign`] - If a prosign name isn't known
+/// * [`Error::BufferOverflow`] - If the codes don't fit in [`BUFFER_LENGTH`]
+pub fn string_to_codes<A: Alphabet + ?Sized>(
+	string: &String<BUFFER_LENGTH>,
+	alphabet: &A,
+) -> Result<[Code; BUFFER_LENGTH], Error> {
+	let mut codes: Vec<Code, BUFFER_LENGTH> = Vec::new();
+
+	encode_each(string, alphabet, |code| {
+		codes.push(code).map_err(|_| Error::BufferOverflow)
+	})?;
+
+	// Fills the rest of the array with empty codes
+	let mut array = [Code::None; BUFFER_LENGTH];
+	array[..codes.len()].copy_from_slice(&codes);
+
+	Ok(array)
+}
+
+/// Reads keyed letters sent as a word on their own as the prosign sharing their marks
+///
+/// `<AR>`, `<AS>`, `<BT>` and `<KN>` are keyed the same as `+`, `&`, `=` and `(`,
+/// which [`Code::from_marks`] reads as punctuation. Prosigns are sent between words rather
+/// than inside them, so punctuation with a space or the start or end of the message on
+/// both sides is taken to be the prosign. Wabun code between `<DO>` and `<SN>` is left alone.
+///
+/// # Arguments
+/// * `codes` - The codes keyed, with [`Code::Space`] between words
+/// * `alphabet` - The alphabet the letters were read with
+pub fn read_standalone_prosigns<A: Alphabet + ?Sized>(codes: &mut [Code], alphabet: &A) {
+	let is_gap = |code: Option<&Code>| matches!(code, None | Some(Code::Space | Code::None));
+	let mut wabun = false;
+
+	for index in 0..codes.len() {
+		match codes[index] {
+			Code::Prosign(Prosign::Do) => wabun = true,
+			Code::Prosign(Prosign::Sn) => wabun = false,
+			Code::Letter(marks) if !wabun => {
+				let is_alone = is_gap(index.checked_sub(1).map(|before| &codes[before]))
+					&& is_gap(codes.get(index + 1));
+				let is_punctuation = alphabet
+					.code_to_char(codes[index])
+					.is_some_and(|char| char.is_ascii_punctuation());
+
+				if let Some(prosign) =
+					Prosign::from_marks(marks).filter(|_| is_alone && is_punctuation)
+				{
+					codes[index] = Code::Prosign(prosign);
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Converts morse codes of any length to a string using the given alphabet,
+/// in the same way as [`codes_to_string`]
+///
+/// # Errors
+/// * [`Error::UnknownCode`] - If a code isn't in the alphabet
+#[cfg(feature = "std")]
+pub fn decode<A: Alphabet + ?Sized>(
+	codes: &[Code],
+	alphabet: &A,
+) -> Result<std::string::String, Error> {
+	let mut string = std::string::String::new();
+
+	decode_each(codes.iter().copied(), alphabet, |char| {
+		string.push(char);
+		Ok(())
+	})?;
+
+	Ok(string)
+}
+
+/// Converts a string of any length to morse codes using the given alphabet,
+/// in the same way as [`string_to_codes`]
+///
+/// # Errors
+/// * [`Error::UnknownCharacter`] - If a character isn't in the alphabet
+/// * [`Error::UnknownProsign`] - If a prosign name isn't known
+#[cfg(feature = "std")]
+pub fn encode<A: Alphabet + ?Sized>(
+	string: &str,
+	alphabet: &A,
+) -> Result<std::vec::Vec<Code>, Error> {
+	let mut codes = std::vec::Vec::new();
+
+	encode_each(string, alphabet, |code| {
+		codes.push(code);
+		Ok(())
+	})?;
+
+	Ok(codes)
+}
+
+/// Decodes codes into characters, passing each one on as soon as it is known
+///
+/// A character is held back until the next code, as a voicing mark may join onto it.
+fn decode_each<A, I, F>(codes: I, alphabet: &A, mut push: F) -> Result<(), Error>
+where
+	A: Alphabet + ?Sized,
+	I: IntoIterator<Item = Code>,
+	F: FnMut(char) -> Result<(), Error>,
+{
+	let mut decoder = Decoder::new(alphabet);
+	let mut held: Option<char> = None;
+
+	for (position, code) in codes.into_iter().enumerate() {
+		let output = decoder.decode(code);
+
+		if let Decoded::Char(char) = output {
+			// Voicing marks join onto the kana before them
+			if let Some(composed) = held.and_then(|kana| wabun::compose(kana, char)) {
+				held = Some(composed);
+				continue;
+			}
+		}
+
+		if output == Decoded::None {
+			continue;
+		}
+
+		if let Some(char) = held.take() {
+			push(char)?;
+		}
+
+		match output {
+			Decoded::Char(char) => held = Some(char),
+			Decoded::Digraph(first, second) => {
+				push(first)?;
+				push(second)?;
+			}
+			Decoded::Prosign(prosign) => {
+				push('<')?;
+				for char in prosign.name().chars() {
+					push(char)?;
+				}
+				push('>')?;
+			}
+			Decoded::Unknown => return Err(Error::UnknownCode { code, position }),
+			Decoded::None => {}
+		}
+	}
+
+	if let Some(char) = held {
+		push(char)?;
+	}
+
+	Ok(())
+}
+
+/// Encodes a string into codes, passing each one on in order
+fn encode_each<A, F>(string: &str, alphabet: &A, mut push: F) -> Result<(), Error>
+where
+	A: Alphabet + ?Sized,
+	F: FnMut(Code) -> Result<(), Error>,
+{
+	let mut chars = string.chars().enumerate();
+	let mut wabun = false;
+
+	while let Some((position, char)) = chars.next() {
+		if char == '<' {
+			let prosign = read_prosign(&mut chars).ok_or(Error::UnknownProsign { position })?;
+			push(Code::Prosign(prosign))?;
+			continue;
+		}
+
+		let (kana, mark) = wabun::decompose(char);
+
+		if let Some(code) = Wabun.char_to_code(kana) {
+			if !wabun {
+				push(Code::Prosign(Prosign::Do))?;
+				wabun = true;
+			}
+			push(code)?;
+			if let Some(mark) = mark.and_then(|mark| Wabun.char_to_code(mark)) {
+				push(mark)?;
+			}
+			continue;
+		}
+
+		if wabun && char != ' ' {
+			push(Code::Prosign(Prosign::Sn))?;
+			wabun = false;
+		}
+
+		let char = to_lowercase(char);
+		let digraph = chars
+			.clone()
+			.next()
+			.and_then(|(_, next)| alphabet.digraph_to_code(char, to_lowercase(next)));
+
+		let code = if let Some(code) = digraph {
+			chars.next();
+			code
+		} else {
+			Code::char_to_code(char, alphabet).ok_or(Error::UnknownCharacter {
+				character: char,
+				position,
+			})?
+		};
+
+		push(code)?;
+	}
+
+	Ok(())
+}
+
+/// Lowercases a character that lowercases to a single character, such as `Ä`
+fn to_lowercase(char: char) -> char { char.to_lowercase().next().unwrap_or(char) }
+
+/// Reads the rest of a prosign up to and including its closing `>`
+fn read_prosign(chars: &mut Enumerate<Chars>) -> Option<Prosign> {
+	let mut name = String::<4>::new();
+
+	for (_, char) in chars.by_ref() {
+		if char == '>' {
+			return Prosign::from_name(&name);
+		}
+		if name.push(char).is_err() {
+			break;
+		}
+	}
+
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::alphabet::Table;
+
+	/// Reads codes from their marks as they are keyed, then finds the prosigns sent on their own
+	fn keyed(text: &str) -> String<STRING_LENGTH> {
+		let alphabet = Table::default();
+		let mut codes = string_to_codes(&text.into(), &alphabet).unwrap();
+
+		for code in &mut codes {
+			if let Some(marks) = code.marks() {
+				*code = Code::from_marks(marks, &alphabet);
+			}
+		}
+		read_standalone_prosigns(&mut codes, &alphabet);
+
+		codes_to_string(&codes, &alphabet).unwrap()
+	}
+
+	#[test]
+	fn reads_punctuation_sent_on_its_own_as_a_prosign() {
+		assert_eq!(keyed("<bt> cq de m0abc <kn>"), "<BT> cq de m0abc <KN>");
+		assert_eq!(keyed("test <as> <ar>"), "test <AS> <AR>");
+		assert_eq!(keyed("<sk> ok"), "<SK> ok");
+	}
+
+	#[test]
+	fn keeps_punctuation_inside_a_word() {
+		assert_eq!(keyed("2+2=4 r&d (a"), "2+2=4 r&d (a");
+		assert_eq!(keyed("? / ."), "? / .");
+	}
+}
@@ -0,0 +1,123 @@
+//! Encodes messages known when compiling, such as callsigns, beacons and greetings
+//!
+//! The [`morse!`](crate::morse) macro encodes a string into an array of codes as a constant,
+//! so a character that can't be sent fails the build rather than the message at runtime.
+//! Messages use the international [`Latin`] alphabet, ignoring case, with prosigns written
+//! in angle brackets as for [`string_to_codes`](crate::string_to_codes).
+//!
+//! ```
+//! use morse::{code::{Code, Prosign}, morse};
+//!
+//! const BEACON: [Code; 13] = morse!("CQ DE M0ABC <AR>");
+//! assert_eq!(BEACON[12], Code::Prosign(Prosign::Ar));
+//! ```
+//!
+//! Characters outside the international alphabet don't compile:
+//!
+//! ```compile_fail
+//! let greeting = morse::morse!("hé");
+//! ```
+//!
+//! Nor do prosigns that aren't known:
+//!
+//! ```compile_fail
+//! let unknown = morse::morse!("<XX>");
+//! ```
+
+use core::str;
+
+use crate::{
+	alphabet::Latin,
+	code::{Code, Prosign},
+};
+
+/// Encodes a message into an array of codes while compiling
+///
+/// The array is as long as the message needs, and an unknown character or prosign
+/// is a compile error.
+#[macro_export]
+macro_rules! morse {
+	($message:expr) => {{
+		const CODES: [$crate::code::Code; $crate::literal::code_count($message)] =
+			$crate::literal::encode_message($message);
+		CODES
+	}};
+}
+
+/// Counts the codes a message is encoded as
+///
+/// # Panics
+/// * If a character isn't in the international alphabet or a prosign name isn't known
+#[must_use]
+pub const fn code_count(message: &str) -> usize {
+	let message = message.as_bytes();
+	let mut count = 0;
+	let mut index = 0;
+
+	while index < message.len() {
+		index = next_code(message, index).1;
+		count += 1;
+	}
+
+	count
+}
+
+/// Encodes a message into codes in a const context
+///
+/// # Panics
+/// * If a character isn't in the international alphabet or a prosign name isn't known
+/// * If `N` isn't the [`code_count`] of the message
+#[must_use]
+pub const fn encode_message<const N: usize>(message: &str) -> [Code; N] {
+	let message = message.as_bytes();
+	let mut codes = [Code::None; N];
+	let mut count = 0;
+	let mut index = 0;
+
+	while index < message.len() {
+		assert!(count < N, "Message has more codes than the array holds");
+
+		let (code, next) = next_code(message, index);
+		codes[count] = code;
+		count += 1;
+		index = next;
+	}
+
+	assert!(count == N, "Message has fewer codes than the array holds");
+
+	codes
+}
+
+/// Encodes the code starting at a byte of a message
+///
+/// # Returns
+/// * `(Code, usize)` - The code, and the index of the byte after it
+///
+/// # Panics
+/// * If the character isn't in the international alphabet or the prosign name isn't known
+const fn next_code(message: &[u8], index: usize) -> (Code, usize) {
+	if message[index] == b'<' {
+		let (_, rest) = message.split_at(index + 1);
+		let mut length = 0;
+
+		while length < rest.len() && rest[length] != b'>' {
+			length += 1;
+		}
+
+		let prosign = match str::from_utf8(rest.split_at(length).0) {
+			Ok(name) if length < rest.len() => Prosign::from_name(name),
+			_ => None,
+		};
+		let Some(prosign) = prosign else {
+			panic!("Unknown prosign in morse message");
+		};
+
+		return (Code::Prosign(prosign), index + length + 2);
+	}
+
+	// Bytes of characters outside ASCII are past the end of the table
+	match Latin::const_char_to_code(message[index].to_ascii_lowercase() as char) {
+		Some(code) => (code, index + 1),
+		None => panic!("Character isn't in the international morse alphabet"),
+	}
+}
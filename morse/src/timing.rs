@@ -0,0 +1,441 @@
+//! Decodes morse code keyed as a stream of on and off spans, learning the speed as it goes
+//!
+//! Any front end that can tell when a signal is on, such as the [`tone`](crate::tone) detector
+//! or the button, passes the length of each span to a [`SpanDecoder`], which turns them into
+//! characters as soon as each letter is finished.
+//!
+//! The lengths of the last few marks are clustered into dots and dashes, so the boundaries follow
+//! the sender speeding up or slowing down. A dash is two units longer than a dot whatever the weight,
+//! and the gap between marks is as much shorter than a unit as a dot is longer. The last few longer
+//! gaps are clustered into those between letters and between words apart from the marks,
+//! so Farnsworth spacing is learnt too.
+
+use heapless::{HistoryBuffer, Vec};
+
+use crate::{
+	alphabet::Alphabet,
+	code::{Code, Mark, Marks, MAX_MARKS},
+	decoder::{Decoded, Decoder},
+	keying::Timing,
+};
+
+/// Number of recent marks, and of recent gaps between letters and words, the speed is learnt from
+const HISTORY_LENGTH: usize = 16;
+/// Ratios of a dash to a dot, and of a gap between words to one between letters
+const DASH_RATIO: (u32, u32) = (3, 1);
+const WORD_RATIO: (u32, u32) = (7, 3);
+
+/// A length of time the signal was on or off for
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+	/// Whether the signal was on
+	pub on:       bool,
+	/// Length in microseconds
+	pub duration: u32,
+}
+
+/// What a gap between marks finishes
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Gap {
+	/// Nothing, the next mark is part of the same letter
+	Element,
+	Letter,
+	Word,
+}
+
+/// A letter or space read by a [`SpanDecoder`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Received {
+	/// Microseconds from the first span to the start of the letter, or of the gap for a space
+	pub time:    u64,
+	pub code:    Code,
+	/// The code decoded with the alphabet, a space is [`Decoded::Char`] of `' '`
+	pub decoded: Decoded,
+}
+
+/// What a single span finishes, a letter followed by a space at most
+pub type Finished = Vec<Received, 2>;
+
+/// Turns on and off spans into characters, estimating the speed from their lengths
+pub struct SpanDecoder<'a, A: Alphabet + ?Sized> {
+	alphabet:     &'a A,
+	decoder:      Decoder<'a, A>,
+	/// Average lengths of each kind of mark and gap in microseconds
+	dot:          u32,
+	dash:         u32,
+	letter_gap:   u32,
+	word_gap:     u32,
+	marks_heard:  HistoryBuffer<u32, HISTORY_LENGTH>,
+	/// Gaps longer than those between marks
+	gaps_heard:   HistoryBuffer<u32, HISTORY_LENGTH>,
+	/// Lengths of the marks of the letter being read, classified once it is finished
+	/// with what has been learnt by then
+	letter:       Vec<u32, MAX_MARKS>,
+	/// Time the span being passed in starts at
+	time:         u64,
+	/// Time the first mark of the letter started at
+	letter_start: u64,
+	/// Whether a mark has been seen since the last space, so gaps before a message are ignored
+	started:      bool,
+	/// Whether the gap going on has already finished a word, so its space is only read once
+	spaced:       bool,
+}
+
+impl<'a, A: Alphabet + ?Sized> SpanDecoder<'a, A> {
+	/// # Arguments
+	/// * `alphabet` - The alphabet to decode letters with
+	/// * `timing` - The speed to start from, before any marks or gaps have been seen
+	#[must_use]
+	pub fn new(alphabet: &'a A, timing: &Timing) -> Self {
+		let unit = timing.unit();
+		let gap_unit = timing.gap_unit();
+
+		Self {
+			alphabet,
+			decoder: Decoder::new(alphabet),
+			dot: unit,
+			dash: unit * 3,
+			letter_gap: gap_unit * 3,
+			word_gap: gap_unit * 7,
+			marks_heard: HistoryBuffer::new(),
+			gaps_heard: HistoryBuffer::new(),
+			letter: Vec::new(),
+			time: 0,
+			letter_start: 0,
+			started: false,
+			spaced: false,
+		}
+	}
+
+	/// The estimated length of a unit in microseconds, which is a dot at standard weight
+	#[must_use]
+	pub const fn unit(&self) -> u32 { self.dash.saturating_sub(self.dot) / 2 }
+
+	/// The estimated length of a gap between marks in microseconds
+	const fn element_gap(&self) -> u32 {
+		let gap = self.dash.saturating_sub(self.dot * 2);
+		if gap > self.dot / 4 {
+			gap
+		} else {
+			self.dot / 4
+		}
+	}
+
+	/// Classifies the length of a mark with the current estimate
+	#[must_use]
+	pub const fn mark(&self, duration: u32) -> Mark {
+		if is_nearer_second(duration, self.dot, self.dash) {
+			Mark::Dash
+		} else {
+			Mark::Dot
+		}
+	}
+
+	/// Classifies the length of a gap with the current estimate
+	#[must_use]
+	pub const fn gap(&self, duration: u32) -> Gap {
+		if is_nearer_second(duration, self.letter_gap, self.word_gap) {
+			Gap::Word
+		} else if is_nearer_second(duration, self.element_gap(), self.letter_gap) {
+			Gap::Letter
+		} else {
+			Gap::Element
+		}
+	}
+
+	/// Passes in the next span
+	///
+	/// Marks past the most a code can hold are dropped.
+	///
+	/// # Returns
+	/// * `Finished` - The letter finished by a gap, followed by a space if the gap is long enough
+	pub fn push(&mut self, span: Span) -> Finished {
+		if !span.on {
+			// Gaps before the message say nothing about the speed
+			if self.started {
+				self.learn_gap(span.duration);
+			}
+
+			let finished = self.wait(span.duration);
+			self.time += u64::from(span.duration);
+
+			if self.spaced {
+				self.started = false;
+				self.spaced = false;
+			}
+
+			return finished;
+		}
+
+		if self.letter.is_empty() {
+			self.letter_start = self.time;
+		}
+
+		self.letter.push(span.duration).unwrap_or_default();
+		self.learn_mark(span.duration);
+		self.started = true;
+		self.time += u64::from(span.duration);
+
+		Finished::new()
+	}
+
+	/// Checks a gap that is still going on, so a letter is finished without waiting for the next mark
+	///
+	/// # Arguments
+	/// * `elapsed` - Microseconds the signal has been off for so far
+	///
+	/// # Returns
+	/// * `Finished` - The letter finished by the gap, followed by a space if it is long enough,
+	///   each only once however many times the gap is checked
+	pub fn wait(&mut self, elapsed: u32) -> Finished {
+		let mut finished = Finished::new();
+		let gap = self.gap(elapsed);
+
+		if gap >= Gap::Letter {
+			if let Some(letter) = self.finish() {
+				finished.push(letter).unwrap_or_default();
+			}
+		}
+
+		if gap == Gap::Word && self.started && !self.spaced {
+			self.spaced = true;
+			let space = self.receive(self.time, Code::Space);
+			finished.push(space).unwrap_or_default();
+		}
+
+		finished
+	}
+
+	/// Whether the letter being read holds as many marks as a code can
+	#[must_use]
+	pub fn is_letter_full(&self) -> bool { self.letter.is_full() }
+
+	/// Drops the letter being read, such as when it has too many marks
+	pub fn clear_letter(&mut self) { self.letter.clear() }
+
+	/// Finishes the letter being read, such as at the end of the stream
+	pub fn finish(&mut self) -> Option<Received> {
+		if self.letter.is_empty() {
+			return None;
+		}
+
+		let mut marks = Marks::new();
+		for length in &self.letter {
+			marks.push(self.mark(*length)).unwrap_or_default();
+		}
+		self.letter.clear();
+
+		let code = Code::from_marks(marks, self.alphabet);
+
+		Some(self.receive(self.letter_start, code))
+	}
+
+	fn receive(&mut self, time: u64, code: Code) -> Received {
+		Received {
+			time,
+			code,
+			decoded: self.decoder.decode(code),
+		}
+	}
+
+	/// Clusters the recent marks again with a new one
+	fn learn_mark(&mut self, duration: u32) {
+		self.marks_heard.write(duration);
+		(self.dot, self.dash) = cluster(&self.marks_heard, self.dot, self.dash, DASH_RATIO);
+	}
+
+	/// Clusters the recent gaps between letters and words again with a new gap,
+	/// if it is longer than between marks
+	fn learn_gap(&mut self, duration: u32) {
+		if duration < self.element_gap() * 2 {
+			return;
+		}
+
+		// Pauses far longer than a word gap are counted as only twice as long
+		self.gaps_heard.write(duration.min(self.word_gap * 2));
+		(self.letter_gap, self.word_gap) =
+			cluster(&self.gaps_heard, self.letter_gap, self.word_gap, WORD_RATIO);
+	}
+}
+
+/// Splits lengths into shorter and longer kinds, with two means clustering
+///
+/// # Arguments
+/// * `lengths` - The lengths heard
+/// * `shorter` - The current average of the shorter kind
+/// * `longer` - The current average of the longer kind
+/// * `ratio` - How many times longer the longer kind is, as a fraction
+///
+/// # Returns
+/// * `(u32, u32)` - The new averages of the shorter and longer kinds. When the lengths are all
+///   much the same they are the kind they are nearest to, and the other kind keeps to the ratio.
+fn cluster(lengths: &[u32], shorter: u32, longer: u32, ratio: (u32, u32)) -> (u32, u32) {
+	let (Some(&shortest), Some(&longest)) = (lengths.iter().min(), lengths.iter().max()) else {
+		return (shorter, longer);
+	};
+	let (numerator, denominator) = (u64::from(ratio.0), u64::from(ratio.1));
+
+	// Closer together than halfway to the ratio is a single kind
+	if u64::from(longest) * 2 * denominator < u64::from(shortest) * (numerator + denominator) {
+		let mean = mean(lengths.iter().copied()).unwrap_or(shortest);
+		let scaled = |length: u32, by: u64, over: u64| {
+			u32::try_from(u64::from(length) * by / over).unwrap_or(u32::MAX)
+		};
+
+		return if is_nearer_second(mean, shorter, longer) {
+			(scaled(mean, denominator, numerator), mean)
+		} else {
+			(mean, scaled(mean, numerator, denominator))
+		};
+	}
+
+	let mut threshold = shortest / 2 + longest / 2;
+	let mut means = (shorter, longer);
+
+	for _ in 0..4 {
+		means = (
+			mean(lengths.iter().copied().filter(|length| *length < threshold)).unwrap_or(means.0),
+			mean(
+				lengths
+					.iter()
+					.copied()
+					.filter(|length| *length >= threshold),
+			)
+			.unwrap_or(means.1),
+		);
+		threshold = means.0 / 2 + means.1 / 2;
+	}
+
+	means
+}
+
+/// The mean of some lengths, if there are any
+fn mean<I: Iterator<Item = u32>>(lengths: I) -> Option<u32> {
+	let (total, count) = lengths.fold((0u64, 0u64), |(total, count), length| {
+		(total + u64::from(length), count + 1)
+	});
+
+	u32::try_from(total.checked_div(count)?).ok()
+}
+
+/// Whether a length is nearer the second of two averages than the first
+const fn is_nearer_second(duration: u32, first: u32, second: u32) -> bool {
+	duration as u64 * 2 >= first as u64 + second as u64
+}
+
+#[cfg(test)]
+mod tests {
+	use heapless::String;
+
+	use super::*;
+	use crate::{
+		alphabet::Table, decoder::Decoded, keying::keying, string_to_codes, BUFFER_LENGTH,
+	};
+
+	const MESSAGE: &str = "paris cq de m0abc test";
+
+	/// Keys the message and decodes it again, starting from a speed
+	fn round_trip(keyed: &Timing, start: &Timing) -> String<BUFFER_LENGTH> {
+		let alphabet = Table::default();
+		let codes = string_to_codes(&String::from(MESSAGE), &alphabet).unwrap();
+		let mut decoder = SpanDecoder::new(&alphabet, start);
+		let mut text = String::new();
+
+		let mut push = |received: Received| {
+			if let Decoded::Char(char) = received.decoded {
+				text.push(char).unwrap();
+			}
+		};
+
+		for span in keying(codes, keyed) {
+			decoder.push(span).into_iter().for_each(&mut push);
+		}
+		decoder.finish().into_iter().for_each(&mut push);
+
+		text
+	}
+
+	#[test]
+	fn decodes_keying_at_its_own_speed() {
+		for wpm in [5.0, 12.0, 20.0, 30.0, 40.0] {
+			let timing = Timing::new(wpm);
+			assert_eq!(
+				round_trip(&timing, &timing).trim_end(),
+				MESSAGE,
+				"{wpm} wpm"
+			);
+		}
+	}
+
+	#[test]
+	fn decodes_farnsworth_spacing_and_weight() {
+		let farnsworth = Timing {
+			farnsworth_wpm: 8.0,
+			..Timing::new(18.0)
+		};
+		let heavy = Timing {
+			weight: 65.0,
+			..Timing::new(20.0)
+		};
+
+		assert_eq!(round_trip(&farnsworth, &farnsworth).trim_end(), MESSAGE);
+		assert_eq!(round_trip(&heavy, &heavy).trim_end(), MESSAGE);
+	}
+
+	#[test]
+	fn learns_a_different_speed() {
+		for (keyed, start) in [(25.0, 15.0), (12.0, 20.0)] {
+			let text = round_trip(&Timing::new(keyed), &Timing::new(start));
+
+			// Letters are classified once they are finished, but the gaps of the first word
+			// are read before the gap lengths have been learnt
+			let letters = |text: &str| {
+				text.chars()
+					.filter(|char| *char != ' ')
+					.eq(MESSAGE.chars().filter(|char| *char != ' '))
+			};
+			assert!(letters(&text), "{keyed} wpm from {start}: {text}");
+			assert!(
+				text.trim_end().ends_with(" cq de m0abc test"),
+				"{keyed} wpm from {start}: {text}"
+			);
+		}
+	}
+
+	#[test]
+	fn learns_word_gaps_while_waiting() {
+		let alphabet = Table::default();
+		let unit = Timing::new(20.0).unit();
+		let mut decoder = SpanDecoder::new(&alphabet, &Timing::new(20.0));
+		let mut spaces = 0;
+
+		// A letter followed by a long gap between words, checked every millisecond as it goes on
+		for _ in 0..20 {
+			decoder.push(Span {
+				on:       true,
+				duration: unit,
+			});
+
+			for elapsed in (0..unit * 14).step_by(1000) {
+				spaces += decoder
+					.wait(elapsed)
+					.iter()
+					.filter(|received| received.code == Code::Space)
+					.count();
+			}
+
+			spaces += decoder
+				.push(Span {
+					on:       false,
+					duration: unit * 14,
+				})
+				.iter()
+				.filter(|received| received.code == Code::Space)
+				.count();
+		}
+
+		assert_eq!(spaces, 20);
+		assert_eq!(decoder.gap(unit * 8), Gap::Letter);
+		assert_eq!(decoder.gap(unit * 14), Gap::Word);
+	}
+}
@@ -0,0 +1,204 @@
+//! Detects a morse tone in audio samples, turning it into on and off [`Span`]s
+//!
+//! The detector only needs samples one at a time, so it works on a recording read on the host
+//! and on samples from an ADC alike.
+
+use core::f32::consts::PI;
+
+use crate::timing::Span;
+
+/// Length of each block of samples the tone is measured over in microseconds
+pub const BLOCK_LENGTH: u32 = 5000;
+
+/// How far the peak or floor level moves towards a block louder or quieter than halfway
+const LEVEL_RATE: f32 = 0.15;
+/// How far the peak level falls towards the floor on each quiet block, in case the tone fades
+const LEVEL_DECAY: f32 = 0.002;
+/// How many times the floor level the peak must be for the tone to be heard at all
+const MIN_CONTRAST: f32 = 4.0;
+
+/// Measures the strength of a single frequency over a block of samples
+#[derive(Copy, Clone, Debug)]
+pub struct Goertzel {
+	coefficient: f32,
+	previous:    f32,
+	before:      f32,
+	count:       u32,
+}
+
+impl Goertzel {
+	/// # Arguments
+	/// * `frequency` - Frequency to measure in hertz
+	/// * `sample_rate` - Samples per second
+	#[must_use]
+	pub fn new(frequency: f32, sample_rate: u32) -> Self {
+		#[allow(clippy::cast_precision_loss)]
+		let omega = 2.0 * PI * frequency / sample_rate as f32;
+
+		Self {
+			coefficient: 2.0 * libm::cosf(omega),
+			previous:    0.0,
+			before:      0.0,
+			count:       0,
+		}
+	}
+
+	/// Adds the next sample
+	pub fn push(&mut self, sample: f32) {
+		let next = libm::fmaf(self.coefficient, self.previous, sample - self.before);
+		self.before = self.previous;
+		self.previous = next;
+		self.count += 1;
+	}
+
+	/// Gets the amplitude of the frequency over the samples since the last call
+	///
+	/// # Returns
+	/// * `f32` - The amplitude, on the same scale as the samples
+	pub fn take_amplitude(&mut self) -> f32 {
+		// previous² + before² - coefficient × previous × before
+		let rest = libm::fmaf(
+			-self.coefficient * self.previous,
+			self.before,
+			self.before * self.before,
+		);
+		let power = libm::fmaf(self.previous, self.previous, rest);
+		#[allow(clippy::cast_precision_loss)]
+		let amplitude = 2.0 * libm::sqrtf(power.max(0.0)) / self.count.max(1) as f32;
+
+		*self = Self {
+			previous: 0.0,
+			before: 0.0,
+			count: 0,
+			..*self
+		};
+
+		amplitude
+	}
+}
+
+/// Decides when a tone is on, with levels that adapt to the signal and background noise
+///
+/// The tone is measured over blocks of [`BLOCK_LENGTH`], so spans are multiples of it.
+/// It is on when a block is above halfway between the levels of the quiet and loud blocks heard recently.
+#[derive(Copy, Clone, Debug)]
+pub struct Detector {
+	goertzel:    Goertzel,
+	sample_rate: u32,
+	/// Samples in each block
+	block_size:  u32,
+	/// Samples passed in so far
+	samples:     u64,
+	/// Sample the current span started at
+	span_start:  u64,
+	on:          bool,
+	peak:        f32,
+	floor:       f32,
+	/// Whether any blocks have been measured, before which the levels are unknown
+	measured:    bool,
+	/// Whether the tone has stood out from the background yet,
+	/// before which it isn't known whether the samples started with it on
+	heard:       bool,
+	/// Sample at the end of the last block louder than halfway
+	loud_until:  u64,
+}
+
+impl Detector {
+	/// # Arguments
+	/// * `frequency` - Frequency of the tone in hertz
+	/// * `sample_rate` - Samples per second
+	#[must_use]
+	pub fn new(frequency: f32, sample_rate: u32) -> Self {
+		Self {
+			goertzel: Goertzel::new(frequency, sample_rate),
+			sample_rate,
+			block_size: (sample_rate / (1_000_000 / BLOCK_LENGTH)).max(1),
+			samples: 0,
+			span_start: 0,
+			on: false,
+			peak: 0.0,
+			floor: 0.0,
+			measured: false,
+			heard: false,
+			loud_until: 0,
+		}
+	}
+
+	/// Adds the next sample
+	///
+	/// # Returns
+	/// * `Option<Span>` - The span that has just finished, when the tone turns on or off
+	pub fn push(&mut self, sample: f32) -> Option<Span> {
+		self.goertzel.push(sample);
+		self.samples += 1;
+
+		if self.goertzel.count < self.block_size {
+			return None;
+		}
+
+		let heard = self.heard;
+		let amplitude = self.goertzel.take_amplitude();
+		let on = self.measure(amplitude);
+		if on == self.on {
+			return None;
+		}
+
+		// The tone takes a few quiet blocks to first stand out, so went off after the last loud one
+		let end = if heard { self.samples } else { self.loud_until };
+
+		let span = self.span_to(end);
+		self.span_start = end;
+		self.on = on;
+		Some(span)
+	}
+
+	/// Finishes the span being measured, at the end of the samples
+	#[must_use]
+	pub fn finish(&self) -> Span { self.span_to(self.samples) }
+
+	/// Updates the levels with a block's amplitude, and decides whether the tone is on
+	fn measure(&mut self, amplitude: f32) -> bool {
+		if !self.measured {
+			self.peak = amplitude;
+			self.floor = amplitude;
+			self.measured = true;
+		}
+
+		// Loud blocks pull the peak towards them and quiet blocks the floor
+		if amplitude >= f32::midpoint(self.peak, self.floor) {
+			self.loud_until = self.samples;
+			self.peak += (amplitude - self.peak) * LEVEL_RATE;
+		} else {
+			self.floor += (amplitude - self.floor) * LEVEL_RATE;
+			self.peak -= (self.peak - self.floor) * LEVEL_DECAY;
+		}
+
+		if self.peak < self.floor * MIN_CONTRAST || self.peak <= 0.0 {
+			return false;
+		}
+
+		let range = self.peak - self.floor;
+
+		// The tone first stands out when it changes, so the span from the start
+		// is on if this block is quiet
+		if !self.heard {
+			self.heard = true;
+			self.on = amplitude < libm::fmaf(range, 0.5, self.floor);
+		}
+
+		// Stays on until a little below halfway and off until a little above, so noise doesn't flicker
+		let threshold = if self.on { 0.4 } else { 0.6 };
+		amplitude > libm::fmaf(range, threshold, self.floor)
+	}
+
+	/// The span from the start of the current one to a sample
+	fn span_to(&self, sample: u64) -> Span {
+		let samples = sample - self.span_start;
+		let duration = samples * 1_000_000 / u64::from(self.sample_rate);
+
+		Span {
+			on:       self.on,
+			duration: u32::try_from(duration).unwrap_or(u32::MAX),
+		}
+	}
+}
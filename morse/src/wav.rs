@@ -0,0 +1,393 @@
+//! Renders morse code as a tone in a 16-bit mono PCM WAV file, and reads it back from one
+//!
+//! Codes are timed by [`keying`], in the same way as the firmware blinks them on the LED.
+
+use std::{
+	f64::consts::PI,
+	io::{self, Read, Write},
+	vec::Vec,
+};
+
+use crate::{
+	alphabet::Alphabet,
+	code::Code,
+	keying::{keying, Timing},
+	timing::{Received, SpanDecoder},
+	tone::Detector,
+};
+
+/// Samples read from a WAV file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+	/// Samples per second
+	pub sample_rate: u32,
+	/// Samples scaled to between -1 and 1
+	pub samples:     Vec<f32>,
+}
+
+/// How the tone is played
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tone {
+	/// Frequency of the tone in hertz
+	pub frequency:   f64,
+	/// How fast the codes are keyed
+	pub timing:      Timing,
+	/// Samples per second
+	pub sample_rate: u32,
+	/// Length of the rise and fall of each mark in milliseconds, which stops it clicking
+	pub envelope:    f64,
+}
+
+impl Default for Tone {
+	/// A 600 Hz tone at the speed the LED blinks at
+	fn default() -> Self {
+		Self {
+			frequency:   600.0,
+			timing:      Timing::default(),
+			sample_rate: 44_100,
+			envelope:    5.0,
+		}
+	}
+}
+
+/// Renders codes as samples of the tone
+///
+/// # Arguments
+/// * `codes` - The codes to render
+/// * `tone` - How the tone is played
+///
+/// # Returns
+/// * `Vec<i16>` - The samples
+#[must_use]
+pub fn render(codes: &[Code], tone: &Tone) -> Vec<i16> {
+	let mut samples = Vec::new();
+	let mut time = 0.0;
+
+	for span in keying(codes.iter().copied(), &tone.timing) {
+		let duration = f64::from(span.duration) / 1_000_000.0;
+
+		time = if span.on {
+			push_mark(&mut samples, tone, time, duration)
+		} else {
+			push_silence(&mut samples, tone, time, duration)
+		};
+	}
+
+	samples
+}
+
+/// Writes samples as a 16-bit mono PCM WAV file
+///
+/// # Errors
+/// * If writing fails, there are too many samples for a WAV file,
+///   or the sample rate is too high for its bytes per second to fit in the header
+pub fn write<W: Write>(mut writer: W, samples: &[i16], sample_rate: u32) -> io::Result<()> {
+	let byte_rate = sample_rate.checked_mul(2).ok_or_else(|| {
+		io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"sample rate too high for a WAV file",
+		)
+	})?;
+
+	let data_length = u32::try_from(samples.len() * 2)
+		.ok()
+		.filter(|length| *length <= u32::MAX - 36)
+		.ok_or_else(|| {
+			io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"too many samples for a WAV file",
+			)
+		})?;
+
+	writer.write_all(b"RIFF")?;
+	writer.write_all(&(36 + data_length).to_le_bytes())?;
+	writer.write_all(b"WAVE")?;
+
+	writer.write_all(b"fmt ")?;
+	writer.write_all(&16u32.to_le_bytes())?;
+	// PCM with one channel
+	writer.write_all(&1u16.to_le_bytes())?;
+	writer.write_all(&1u16.to_le_bytes())?;
+	writer.write_all(&sample_rate.to_le_bytes())?;
+	// Bytes per second, bytes per sample and bits per sample
+	writer.write_all(&byte_rate.to_le_bytes())?;
+	writer.write_all(&2u16.to_le_bytes())?;
+	writer.write_all(&16u16.to_le_bytes())?;
+
+	writer.write_all(b"data")?;
+	writer.write_all(&data_length.to_le_bytes())?;
+	for sample in samples {
+		writer.write_all(&sample.to_le_bytes())?;
+	}
+
+	writer.flush()
+}
+
+/// Adds a mark of the tone, rising and falling over the envelope
+///
+/// # Returns
+/// * `f64` - The time in seconds at the end of the mark
+fn push_mark(samples: &mut Vec<i16>, tone: &Tone, time: f64, duration: f64) -> f64 {
+	let end = time + duration;
+	let count = sample_index(end, tone.sample_rate).saturating_sub(samples.len());
+	let sample_rate = f64::from(tone.sample_rate);
+	// Marks shorter than the envelope rise for half and fall for the other half
+	let envelope = (tone.envelope / 1000.0).min(duration / 2.0);
+
+	for index in 0..count {
+		#[allow(clippy::cast_precision_loss)]
+		let offset = index as f64 / sample_rate;
+		let ramp = offset.min(duration - offset);
+		let level = if ramp < envelope {
+			(1.0 - (PI * ramp / envelope).cos()) / 2.0
+		} else {
+			1.0
+		};
+
+		let value = (2.0 * PI * tone.frequency * offset).sin() * level * 0.8;
+		#[allow(clippy::cast_possible_truncation)]
+		samples.push((value * f64::from(i16::MAX)) as i16);
+	}
+
+	end
+}
+
+/// Adds silence
+///
+/// # Returns
+/// * `f64` - The time in seconds at the end of the silence
+fn push_silence(samples: &mut Vec<i16>, tone: &Tone, time: f64, duration: f64) -> f64 {
+	let end = time + duration;
+	samples.resize(sample_index(end, tone.sample_rate), 0);
+	end
+}
+
+/// The index of the sample at a time in seconds, so rounding doesn't build up over a message
+fn sample_index(time: f64, sample_rate: u32) -> usize {
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	let index = (time * f64::from(sample_rate)).round() as usize;
+	index
+}
+
+/// Reads a mono WAV file of 8, 16, 24 or 32-bit PCM or 32-bit float samples
+///
+/// # Errors
+/// * If reading fails, or the file isn't a mono WAV file in one of those formats
+pub fn read<R: Read>(mut reader: R) -> io::Result<Recording> {
+	let mut bytes = Vec::new();
+	reader.read_to_end(&mut bytes)?;
+
+	if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+		return Err(invalid_data("not a WAV file"));
+	}
+
+	let mut format = None;
+	let mut chunks = &bytes[12..];
+
+	while chunks.len() >= 8 {
+		let id = &chunks[..4];
+		let length = u32::from_le_bytes([chunks[4], chunks[5], chunks[6], chunks[7]]) as usize;
+		let chunk = &chunks[8..chunks.len().min(8 + length)];
+
+		match id {
+			b"fmt " if chunk.len() >= 16 => {
+				let field = |index: usize| u16::from_le_bytes([chunk[index], chunk[index + 1]]);
+				let sample_rate = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+				// Extensible files give the encoding at the start of their sub-format
+				let encoding = match field(0) {
+					0xFFFE if chunk.len() >= 26 => field(24),
+					encoding => encoding,
+				};
+				format = Some((encoding, field(2), sample_rate, field(14)));
+			}
+			b"data" => {
+				let (encoding, channels, sample_rate, bits) =
+					format.ok_or_else(|| invalid_data("the data comes before the format"))?;
+
+				if channels != 1 {
+					return Err(invalid_data("only mono WAV files can be read"));
+				}
+				if sample_rate == 0 {
+					return Err(invalid_data("the sample rate is zero"));
+				}
+
+				let samples = read_samples(chunk, encoding, bits)?;
+				return Ok(Recording {
+					sample_rate,
+					samples,
+				});
+			}
+			_ => {}
+		}
+
+		// Chunks are padded to an even length
+		let next = (8 + length + length % 2).min(chunks.len());
+		chunks = &chunks[next..];
+	}
+
+	Err(invalid_data("no samples in the WAV file"))
+}
+
+/// Listens for the tone in a recording, decoding it as the speed is learnt
+///
+/// # Arguments
+/// * `recording` - The recording to listen to
+/// * `tone` - The frequency of the tone, and the speed to start from
+/// * `alphabet` - The alphabet to decode letters with
+///
+/// # Returns
+/// * `Vec<Received>` - The letters and spaces heard, in order
+#[must_use]
+pub fn listen<A: Alphabet + ?Sized>(
+	recording: &Recording,
+	tone: &Tone,
+	alphabet: &A,
+) -> Vec<Received> {
+	#[allow(clippy::cast_possible_truncation)]
+	let mut detector = Detector::new(tone.frequency as f32, recording.sample_rate);
+	let mut decoder = SpanDecoder::new(alphabet, &tone.timing);
+
+	let mut received = Vec::new();
+
+	for sample in &recording.samples {
+		if let Some(span) = detector.push(*sample) {
+			received.extend(decoder.push(span));
+		}
+	}
+
+	received.extend(decoder.push(detector.finish()));
+	received.extend(decoder.finish());
+
+	received
+}
+
+/// Converts the bytes of the data chunk to samples
+fn read_samples(data: &[u8], encoding: u16, bits: u16) -> io::Result<Vec<f32>> {
+	// Integer PCM, or floating point
+	#[allow(clippy::cast_precision_loss)]
+	let samples = match (encoding, bits) {
+		(1, 8) => data
+			.iter()
+			.map(|byte| (f32::from(*byte) - 128.0) / 128.0)
+			.collect(),
+		(1, 16) => data
+			.chunks_exact(2)
+			.map(|bytes| f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / 32768.0)
+			.collect(),
+		(1, 24) => data
+			.chunks_exact(3)
+			.map(|bytes| {
+				i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) as f32 / 2_147_483_648.0
+			})
+			.collect(),
+		(1, 32) => data
+			.chunks_exact(4)
+			.map(|bytes| {
+				i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32
+					/ 2_147_483_648.0
+			})
+			.collect(),
+		(3, 32) => data
+			.chunks_exact(4)
+			.map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+			.collect(),
+		_ => {
+			return Err(invalid_data(
+				"only PCM and 32-bit float WAV files can be read",
+			))
+		}
+	};
+
+	Ok(samples)
+}
+
+fn invalid_data(message: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, message) }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{alphabet::Table, decoder::Decoded, encode};
+
+	const MESSAGE: &str = "paris cq de m0abc test";
+
+	/// Renders the message as a tone, writes it as a WAV file and reads it back
+	fn recording(tone: &Tone) -> Recording {
+		let codes = encode(MESSAGE, &Table::default()).unwrap();
+
+		recording_of(&render(&codes, tone), tone.sample_rate)
+	}
+
+	/// Writes samples as a WAV file and reads them back
+	fn recording_of(samples: &[i16], sample_rate: u32) -> Recording {
+		let mut file = Vec::new();
+		write(&mut file, samples, sample_rate).unwrap();
+
+		read(file.as_slice()).unwrap()
+	}
+
+	fn heard(recording: &Recording, tone: &Tone) -> String {
+		listen(recording, tone, &Table::default())
+			.into_iter()
+			.filter_map(|received| match received.decoded {
+				Decoded::Char(char) => Some(char),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn listens_to_a_rendered_message() {
+		for wpm in [6.0, 15.0, 25.0] {
+			let tone = Tone {
+				frequency: 700.0,
+				timing: Timing::new(wpm),
+				..Tone::default()
+			};
+
+			assert_eq!(
+				heard(&recording(&tone), &tone).trim_end(),
+				MESSAGE,
+				"{wpm} wpm"
+			);
+		}
+	}
+
+	#[test]
+	fn reads_back_the_samples_written() {
+		let tone = Tone {
+			sample_rate: 8000,
+			..Tone::default()
+		};
+		let codes = encode("e", &Table::default()).unwrap();
+		let samples = render(&codes, &tone);
+		let recording = recording_of(&samples, tone.sample_rate);
+
+		assert_eq!(recording.sample_rate, 8000);
+		assert_eq!(recording.samples.len(), samples.len());
+		assert!(recording
+			.samples
+			.iter()
+			.zip(&samples)
+			.all(|(read, written)| (*read - f32::from(*written) / 32768.0).abs() < f32::EPSILON));
+	}
+
+	#[test]
+	fn rejects_files_that_are_not_mono_wav() {
+		let mut stereo = Vec::new();
+		write(&mut stereo, &[0; 4], 8000).unwrap();
+		// Sets the number of channels in the format chunk to two
+		stereo[22] = 2;
+
+		assert!(read(b"not a wav file".as_slice()).is_err());
+		assert!(read(stereo.as_slice()).is_err());
+	}
+
+	#[test]
+	fn rejects_sample_rates_too_high_for_the_header() {
+		let mut file = Vec::new();
+		let error = write(&mut file, &[0; 4], u32::MAX / 2 + 1).unwrap_err();
+
+		assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+		assert!(file.is_empty());
+	}
+}
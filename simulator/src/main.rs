@@ -0,0 +1,102 @@
+//! Replays a key press trace through replay mode, printing the LED changes
+//! and serial output the Pico would have produced
+//!
+//! Usage: `simulator <trace file, or - for stdin> [alphabet]`
+//!
+//! The trace format is described in [`device::trace`]. It is read and replayed by the same
+//! code as replay mode on the Pico, so lines it can't use are reported in the serial output.
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::{
+	env, fs,
+	io::{self, Read},
+	process::ExitCode,
+};
+
+use device::{
+	mock::{MockClock, MockPinSet, MockSerial},
+	replay_trace,
+	trace::read_trace,
+};
+use morse::alphabet::Table;
+
+const USAGE: &str = "Usage: simulator <trace file, or - for stdin> [alphabet]";
+
+fn main() -> ExitCode {
+	let mut args = env::args().skip(1);
+
+	let Some(path) = args.next() else {
+		eprintln!("{USAGE}");
+		return ExitCode::FAILURE;
+	};
+
+	// An empty name keeps the default, as in the alphabet prompt
+	let alphabet = match args.next().filter(|name| !name.is_empty()) {
+		None => Table::default(),
+		Some(name) => {
+			if let Some(table) = Table::from_name(&name) {
+				table
+			} else {
+				let names: Vec<&str> = Table::ALL.iter().map(|table| table.name()).collect();
+				eprintln!(
+					"Unknown alphabet {name}, expected one of {}",
+					names.join(", ")
+				);
+				return ExitCode::FAILURE;
+			}
+		}
+	};
+
+	let text = match read_input(&path) {
+		Ok(text) => text,
+		Err(error) => {
+			eprintln!("Could not read {path}: {error}");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	// Pasted as it would be over serial, finishing with `end` if the trace doesn't
+	let mut serial = MockSerial::new(&format!("{text}\r\nend\r\n"));
+	let events = read_trace(&mut serial);
+
+	// Starts from the first press, as replay mode does
+	let mut clock = MockClock::starting_at(events.first().map_or(0, |event| event.time));
+	let mut pin_set = MockPinSet::new(clock.clone(), Vec::new());
+
+	// The replay and the serial output share one clock, so the LED changes are timed as replayed
+	replay_trace(
+		&events,
+		&mut pin_set,
+		&mut clock.clone(),
+		&mut clock,
+		&mut serial,
+		alphabet,
+	);
+
+	println!("LEDs");
+	for transition in &pin_set.transitions {
+		#[allow(clippy::cast_precision_loss)]
+		let seconds = transition.time as f64 / 1_000_000.0;
+		let state = if transition.on { "on" } else { "off" };
+
+		println!("{seconds:>10.3} s  {:<16} {state}", transition.led.name());
+	}
+
+	println!();
+	println!("Serial");
+	println!("{}", serial.output().replace('\r', ""));
+
+	ExitCode::SUCCESS
+}
+
+/// Reads a trace from a file, or from stdin when the path is `-`
+fn read_input(path: &str) -> io::Result<String> {
+	if path == "-" {
+		let mut text = String::new();
+		io::stdin().read_to_string(&mut text)?;
+		Ok(text)
+	} else {
+		fs::read_to_string(path)
+	}
+}